@@ -0,0 +1,41 @@
+//! Benchmarks on puzzle input chosen at runtime.
+
+use crate::input::{self, split_args};
+use criterion::Criterion;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{0}")]
+    Input(#[from] input::Error),
+    #[error("Only a benchmark filter can be given with --input, not {0}")]
+    UnsupportedArgument(String),
+}
+
+/// Runs benchmarks on the input for a day, chosen the way [`input::load`] does.
+///
+/// Criterion exits on arguments it does not know, so it only reads the command
+/// line when there is no `--input`. With one, the only other argument allowed
+/// is a filter on benchmark names, besides the `--bench` flag cargo passes.
+pub fn run<F>(day: u8, fallback: &'static str, benchmark: F) -> Result<(), Error>
+where
+    F: FnOnce(&mut Criterion, &str),
+{
+    let (source, args) = split_args(std::env::args().skip(1))?;
+    let mut criterion = Criterion::default();
+    if source.is_none() {
+        criterion = criterion.configure_from_args();
+    } else {
+        for arg in args {
+            match arg.as_str() {
+                "--bench" => {}
+                _ if arg.starts_with('-') => return Err(Error::UnsupportedArgument(arg)),
+                _ => criterion = criterion.with_filter(arg),
+            }
+        }
+    }
+    let input = input::select(source, day, fallback)?;
+    benchmark(&mut criterion, &input);
+    criterion.final_summary();
+    Ok(())
+}
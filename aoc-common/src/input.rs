@@ -0,0 +1,188 @@
+use std::{
+    borrow::Cow,
+    io::Read,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Environment variable naming a directory of puzzle inputs.
+///
+/// The input for a day is read from `dayNN.txt` or `dayNN/input.txt` inside the directory.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error reading {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Error reading stdin: {0}")]
+    Stdin(std::io::Error),
+    #[error("No input for day {day} in {dir}")]
+    NotFound { day: u8, dir: PathBuf },
+    #[error("Missing value for --input")]
+    MissingValue,
+}
+
+/// Where to read puzzle input from at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// Interprets a command line value, where `-` means stdin.
+    pub fn parse(value: &str) -> Self {
+        if value == "-" {
+            Source::Stdin
+        } else {
+            Source::File(value.into())
+        }
+    }
+
+    pub fn read(&self) -> Result<String, Error> {
+        match self {
+            Source::File(path) => read_file(path),
+            Source::Stdin => {
+                let mut buffer = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut buffer)
+                    .map_err(Error::Stdin)?;
+                Ok(buffer)
+            }
+        }
+    }
+}
+
+/// Loads the input for a day binary.
+///
+/// Uses `--input <FILE>` from the command line if given (`-` for stdin), then
+/// [`INPUT_DIR_VAR`], and finally the `fallback` compiled into the day's crate.
+pub fn load(day: u8, fallback: &'static str) -> Result<Cow<'static, str>, Error> {
+    select(source_from_args(std::env::args().skip(1))?, day, fallback)
+}
+
+/// Reads `source` if there is one, or else falls back like [`load`].
+pub fn select(
+    source: Option<Source>,
+    day: u8,
+    fallback: &'static str,
+) -> Result<Cow<'static, str>, Error> {
+    match source {
+        Some(source) => source.read().map(Cow::Owned),
+        None => from_env(day, fallback),
+    }
+}
+
+/// Loads the input for a day from [`INPUT_DIR_VAR`] if it is set, or returns `fallback`.
+pub fn from_env(day: u8, fallback: &'static str) -> Result<Cow<'static, str>, Error> {
+    match std::env::var_os(INPUT_DIR_VAR) {
+        Some(dir) => from_dir(Path::new(&dir), day).map(Cow::Owned),
+        None => Ok(Cow::Borrowed(fallback)),
+    }
+}
+
+/// Reads the input for a day from `dayNN.txt` or `dayNN/input.txt` inside `dir`.
+pub fn from_dir(dir: &Path, day: u8) -> Result<String, Error> {
+    let name = format!("day{day:02}");
+    [
+        dir.join(format!("{name}.txt")),
+        dir.join(&name).join("input.txt"),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+    .ok_or_else(|| Error::NotFound {
+        day,
+        dir: dir.to_path_buf(),
+    })
+    .and_then(|path| read_file(&path))
+}
+
+/// Finds `--input <FILE>` or `--input=<FILE>` in the command line arguments.
+pub fn source_from_args<I>(args: I) -> Result<Option<Source>, Error>
+where
+    I: IntoIterator<Item = String>,
+{
+    split_args(args).map(|(source, _)| source)
+}
+
+/// Like [`source_from_args`], also returning the arguments other than `--input`.
+pub fn split_args<I>(args: I) -> Result<(Option<Source>, Vec<String>), Error>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    let mut source = None;
+    let mut rest = vec![];
+    while let Some(arg) = args.next() {
+        if arg == "--input" {
+            let value = args.next().ok_or(Error::MissingValue)?;
+            source = Some(Source::parse(&value));
+        } else if let Some(value) = arg.strip_prefix("--input=") {
+            source = Some(Source::parse(value));
+        } else {
+            rest.push(arg);
+        }
+    }
+    Ok((source, rest))
+}
+
+fn read_file(path: &Path) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(|source| Error::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn args_without_input() {
+        assert_eq!(source_from_args(args("--release")).unwrap(), None);
+    }
+
+    #[test]
+    fn args_with_input() {
+        assert_eq!(
+            source_from_args(args("--input puzzle.txt")).unwrap(),
+            Some(Source::File("puzzle.txt".into()))
+        );
+        assert_eq!(
+            source_from_args(args("--input=-")).unwrap(),
+            Some(Source::Stdin)
+        );
+        assert!(matches!(
+            source_from_args(args("--input")),
+            Err(Error::MissingValue)
+        ));
+        assert_eq!(
+            split_args(args("--bench --input - day01")).unwrap(),
+            (Some(Source::Stdin), args("--bench day01"))
+        );
+    }
+
+    #[test]
+    fn input_dir() {
+        let dir = std::env::temp_dir().join(format!("aoc-input-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("day02")).unwrap();
+        std::fs::write(dir.join("day01.txt"), "one").unwrap();
+        std::fs::write(dir.join("day02").join("input.txt"), "two").unwrap();
+
+        assert_eq!(from_dir(&dir, 1).unwrap(), "one");
+        assert_eq!(from_dir(&dir, 2).unwrap(), "two");
+        assert!(matches!(
+            from_dir(&dir, 3),
+            Err(Error::NotFound { day: 3, .. })
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
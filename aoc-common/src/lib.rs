@@ -0,0 +1,6 @@
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod input;
+pub mod solution;
+
+pub use solution::{Solution, Solver};
@@ -0,0 +1,84 @@
+use std::{error::Error, fmt::Display};
+
+/// A solver for one part of one day's puzzle.
+pub trait Solution: Send + Sync {
+    /// The day of the puzzle, from 1 to 25.
+    fn day(&self) -> u8;
+
+    /// The part of the puzzle, 1 or 2.
+    fn part(&self) -> u8;
+
+    /// Solves the puzzle for the given input.
+    fn solve(&self, input: &str) -> Result<Box<dyn Display>, Box<dyn Error + Send + Sync>>;
+}
+
+/// Implements [`Solution`] for a day's `solve` function.
+pub struct Solver<T, E> {
+    day: u8,
+    part: u8,
+    solve: fn(&str) -> Result<T, E>,
+}
+
+impl<T, E> Solver<T, E> {
+    pub const fn new(day: u8, part: u8, solve: fn(&str) -> Result<T, E>) -> Self {
+        Self { day, part, solve }
+    }
+}
+
+impl<T, E> Solution for Solver<T, E>
+where
+    T: Display + 'static,
+    E: Error + Send + Sync + 'static,
+{
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn part(&self) -> u8 {
+        self.part
+    }
+
+    fn solve(&self, input: &str) -> Result<Box<dyn Display>, Box<dyn Error + Send + Sync>> {
+        match (self.solve)(input) {
+            Ok(answer) => Ok(Box::new(answer)),
+            Err(error) => Err(Box::new(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Invalid;
+
+    impl Display for Invalid {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "invalid input")
+        }
+    }
+
+    impl Error for Invalid {}
+
+    fn count_lines(input: &str) -> Result<usize, Invalid> {
+        match input.lines().count() {
+            0 => Err(Invalid),
+            count => Ok(count),
+        }
+    }
+
+    static SOLUTIONS: [&dyn Solution; 1] = [&Solver::new(3, 2, count_lines)];
+
+    #[test]
+    fn solver() {
+        let solution = SOLUTIONS[0];
+        assert_eq!(solution.day(), 3);
+        assert_eq!(solution.part(), 2);
+        assert_eq!(solution.solve("a\nb\n").unwrap().to_string(), "2");
+        let Err(error) = solution.solve("") else {
+            panic!("expected an error for empty input");
+        };
+        assert_eq!(error.to_string(), "invalid input");
+    }
+}
@@ -0,0 +1,309 @@
+use crate::Point;
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Row {row} has length {length}, expected {width}")]
+    RaggedRow {
+        row: usize,
+        length: usize,
+        width: usize,
+    },
+    #[error("Grid of {width}x{height} needs {expected} cells, got {actual}")]
+    WrongSize {
+        width: usize,
+        height: usize,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+/// A rectangular grid of cells stored in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Result<Self, Error> {
+        if cells.len() == width * height {
+            Ok(Self {
+                width,
+                height,
+                cells,
+            })
+        } else {
+            Err(Error::WrongSize {
+                width,
+                height,
+                expected: width * height,
+                actual: cells.len(),
+            })
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |column| Point::new(row as i32, column as i32)))
+            .map(&mut f)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.height as i32).contains(&point.row)
+            && (0..self.width as i32).contains(&point.column)
+    }
+
+    /// The position of a point in row-major order, if it is inside the grid.
+    pub fn index_of(&self, point: Point) -> Option<usize> {
+        if self.contains(point) {
+            Some(point.row as usize * self.width + point.column as usize)
+        } else {
+            None
+        }
+    }
+
+    /// The point at a row-major position.
+    pub fn point_of(&self, index: usize) -> Point {
+        Point::new((index / self.width) as i32, (index % self.width) as i32)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|index| &mut self.cells[index])
+    }
+
+    /// Swaps the contents of two cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either point is outside the grid.
+    pub fn swap(&mut self, a: Point, b: Point) {
+        let a = self.expect_index(a);
+        let b = self.expect_index(b);
+        self.cells.swap(a, b);
+    }
+
+    /// All points in the grid in row-major order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.cells.len())
+            .map(move |index| Point::new((index / width) as i32, (index % width) as i32))
+    }
+
+    /// All cells with their points in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// The orthogonal neighbours of a point that lie inside the grid.
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        point
+            .neighbours()
+            .filter_map(move |neighbour| Some((neighbour, self.get(neighbour)?)))
+    }
+
+    /// The first point in row-major order whose cell matches the predicate.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(&mut predicate)
+            .map(|index| self.point_of(index))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn expect_index(&self, point: Point) -> usize {
+        self.index_of(point).unwrap_or_else(|| {
+            panic!(
+                "point {point:?} is outside the {}x{} grid",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl Grid<u8> {
+    /// Parses a grid with one byte per cell and one line per row.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let mut width = None;
+        let mut cells = Vec::with_capacity(input.len());
+        let mut height = 0;
+        for (row, line) in input.trim_end_matches(['\n', '\r']).lines().enumerate() {
+            let width = *width.get_or_insert(line.len());
+            if line.len() != width {
+                return Err(Error::RaggedRow {
+                    row,
+                    length: line.len(),
+                    width,
+                });
+            }
+            cells.extend_from_slice(line.as_bytes());
+            height += 1;
+        }
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    /// The first point in row-major order holding the byte.
+    pub fn find(&self, byte: u8) -> Option<Point> {
+        self.position(|&cell| cell == byte)
+    }
+
+    /// All points holding the byte, in row-major order.
+    pub fn find_all(&self, byte: u8) -> impl Iterator<Item = Point> + '_ {
+        self.iter()
+            .filter(move |(_, &cell)| cell == byte)
+            .map(|(point, _)| point)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        &self.cells[self.expect_index(point)]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let index = self.expect_index(point);
+        &mut self.cells[index]
+    }
+}
+
+impl Display for Grid<u8> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for &cell in row {
+                write!(f, "{}", cell as char)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "#.S\n..#\nE..\n";
+
+    #[test]
+    fn parse() {
+        let grid = Grid::parse(EXAMPLE).unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 3);
+        assert_eq!(grid[Point::new(1, 2)], b'#');
+        assert_eq!(grid.find(b'S'), Some(Point::new(0, 2)));
+        assert_eq!(grid.find(b'E'), Some(Point::new(2, 0)));
+        assert_eq!(
+            grid.find_all(b'#').collect::<Vec<_>>(),
+            [Point::new(0, 0), Point::new(1, 2)]
+        );
+        assert_eq!(grid.to_string(), EXAMPLE);
+    }
+
+    #[test]
+    fn ragged() {
+        assert_eq!(
+            Grid::parse("...\n..\n"),
+            Err(Error::RaggedRow {
+                row: 1,
+                length: 2,
+                width: 3
+            })
+        );
+    }
+
+    #[test]
+    fn bounds() {
+        let grid = Grid::new(4, 2, 0);
+        assert!(grid.contains(Point::new(1, 3)));
+        assert!(!grid.contains(Point::new(2, 0)));
+        assert!(!grid.contains(Point::new(0, -1)));
+        assert_eq!(grid.get(Point::new(0, 4)), None);
+        assert_eq!(grid.index_of(Point::new(1, 1)), Some(5));
+        assert_eq!(grid.point_of(5), Point::new(1, 1));
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = Grid::from_fn(3, 3, |point| point.row * 3 + point.column);
+        let corner = grid
+            .neighbours(Point::new(0, 0))
+            .map(|(_, &value)| value)
+            .collect::<Vec<_>>();
+        assert_eq!(corner, [1, 3]);
+        assert_eq!(grid.neighbours(Point::new(1, 1)).count(), 4);
+    }
+
+    #[test]
+    fn swap_and_map() {
+        let mut grid = Grid::parse("ab\ncd").unwrap();
+        grid.swap(Point::new(0, 0), Point::new(1, 1));
+        assert_eq!(grid.to_string(), "db\nca\n");
+        let upper = grid.map(|cell| cell.to_ascii_uppercase());
+        assert_eq!(upper.to_string(), "DB\nCA\n");
+    }
+}
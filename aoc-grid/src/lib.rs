@@ -0,0 +1,8 @@
+pub mod direction;
+pub mod grid;
+pub mod point;
+pub mod search;
+
+pub use direction::Direction;
+pub use grid::{Error, Grid};
+pub use point::Point;
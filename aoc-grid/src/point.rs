@@ -0,0 +1,130 @@
+use crate::Direction;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position or offset on a grid. Points outside the grid are allowed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: i32,
+    pub column: i32,
+}
+
+impl Point {
+    pub const ZERO: Point = Point::new(0, 0);
+
+    /// Offsets to the eight surrounding points, in reading order.
+    pub const SURROUNDING: [Point; 8] = [
+        Point::new(-1, -1),
+        Point::new(-1, 0),
+        Point::new(-1, 1),
+        Point::new(0, -1),
+        Point::new(0, 1),
+        Point::new(1, -1),
+        Point::new(1, 0),
+        Point::new(1, 1),
+    ];
+
+    pub const fn new(row: i32, column: i32) -> Self {
+        Self { row, column }
+    }
+
+    /// The adjacent point in the given direction.
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+
+    /// The four orthogonally adjacent points, in the order of [`Direction::ALL`].
+    pub fn neighbours(self) -> impl Iterator<Item = Point> {
+        Direction::ALL
+            .into_iter()
+            .map(move |direction| self.step(direction))
+    }
+
+    /// The eight surrounding points, including diagonals.
+    pub fn surrounding(self) -> impl Iterator<Item = Point> {
+        Point::SURROUNDING
+            .into_iter()
+            .map(move |offset| self + offset)
+    }
+
+    pub fn manhattan_distance(self, other: Point) -> u32 {
+        self.row.abs_diff(other.row) + self.column.abs_diff(other.column)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.row + rhs.row, self.column + rhs.column)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.row - rhs.row, self.column - rhs.column)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: i32) -> Point {
+        Point::new(self.row * rhs, self.column * rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.row, -self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Point::new(3, 4);
+        let b = Point::new(1, -2);
+        assert_eq!(a + b, Point::new(4, 2));
+        assert_eq!(a - b, Point::new(2, 6));
+        assert_eq!(b * 3, Point::new(3, -6));
+        assert_eq!(-b, Point::new(-1, 2));
+        assert_eq!(a.manhattan_distance(b), 8);
+    }
+
+    #[test]
+    fn neighbours() {
+        let point = Point::new(5, 5);
+        assert_eq!(
+            point.neighbours().collect::<Vec<_>>(),
+            [
+                Point::new(4, 5),
+                Point::new(5, 6),
+                Point::new(6, 5),
+                Point::new(5, 4)
+            ]
+        );
+        assert_eq!(point.surrounding().count(), 8);
+        assert!(point
+            .surrounding()
+            .all(|other| other != point && other.row.abs_diff(5) <= 1));
+    }
+}
@@ -0,0 +1,490 @@
+//! Shortest path searches over states numbered densely from zero, such as the cells
+//! of a grid or (cell, direction) pairs, with non-negative integer costs.
+//!
+//! Distances are kept in flat arrays indexed by state and the open set is a radix
+//! heap, which suits the small integer costs of grid puzzles.
+
+use std::mem;
+
+const NONE: usize = usize::MAX;
+
+/// A priority queue for searches where the smallest key never decreases.
+///
+/// Items are sorted into buckets by the highest bit in which their key differs from
+/// the last key popped, so each item is only moved a few times.
+#[derive(Debug, Clone)]
+pub struct RadixHeap<T> {
+    last: usize,
+    len: usize,
+    buckets: Vec<Vec<(usize, T)>>,
+}
+
+impl<T> RadixHeap<T> {
+    pub fn new() -> Self {
+        Self {
+            last: 0,
+            len: 0,
+            buckets: (0..=usize::BITS).map(|_| vec![]).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `key` is less than the last key popped.
+    pub fn push(&mut self, key: usize, value: T) {
+        assert!(
+            key >= self.last,
+            "key {key} is below the last key popped, {}",
+            self.last
+        );
+        self.buckets[bucket(key, self.last)].push((key, value));
+        self.len += 1;
+    }
+
+    /// The smallest key in the heap.
+    pub fn min_key(&mut self) -> Option<usize> {
+        self.refill();
+        self.buckets[0].last().map(|&(key, _)| key)
+    }
+
+    /// Removes an item with the smallest key.
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        self.refill();
+        let item = self.buckets[0].pop()?;
+        self.len -= 1;
+        Some(item)
+    }
+
+    /// Makes sure the first bucket, which holds the items with the last key, is not
+    /// empty unless the heap is.
+    fn refill(&mut self) {
+        if !self.buckets[0].is_empty() {
+            return;
+        }
+        let Some(index) = self.buckets.iter().position(|bucket| !bucket.is_empty()) else {
+            return;
+        };
+        let items = mem::take(&mut self.buckets[index]);
+        self.last = items.iter().map(|&(key, _)| key).min().unwrap_or(self.last);
+        for (key, value) in items {
+            self.buckets[bucket(key, self.last)].push((key, value));
+        }
+    }
+}
+
+impl<T> Default for RadixHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn bucket(key: usize, last: usize) -> usize {
+    (usize::BITS - (key ^ last).leading_zeros()) as usize
+}
+
+/// The distance to every state from the nearest start, with the state before it on
+/// a shortest path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distances {
+    distances: Vec<usize>,
+    parents: Vec<usize>,
+}
+
+impl Distances {
+    fn new(len: usize) -> Self {
+        Self {
+            distances: vec![NONE; len],
+            parents: vec![NONE; len],
+        }
+    }
+
+    /// The number of states, reached or not.
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+
+    /// The distance to a state, or `None` if it cannot be reached.
+    pub fn get(&self, state: usize) -> Option<usize> {
+        self.distances
+            .get(state)
+            .copied()
+            .filter(|&distance| distance != NONE)
+    }
+
+    /// Every reachable state with its distance.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.distances
+            .iter()
+            .enumerate()
+            .filter(|&(_, &distance)| distance != NONE)
+            .map(|(state, &distance)| (state, distance))
+    }
+
+    /// The states on a shortest path from a start to `state`, inclusive.
+    pub fn path(&self, state: usize) -> Option<Vec<usize>> {
+        self.get(state)?;
+        let mut path = vec![state];
+        while let Some(&parent) = self.parents.get(*path.last()?).filter(|&&p| p != NONE) {
+            path.push(parent);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Runs Dijkstra's algorithm until `stop` accepts a state, returning the distances
+/// found so far and that state.
+fn run<I>(
+    len: usize,
+    starts: impl IntoIterator<Item = usize>,
+    mut successors: impl FnMut(usize) -> I,
+    mut stop: impl FnMut(usize) -> bool,
+) -> (Distances, Option<usize>)
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    let mut distances = Distances::new(len);
+    let mut open = RadixHeap::new();
+    for start in starts {
+        distances.distances[start] = 0;
+        open.push(0, start);
+    }
+    while let Some((distance, state)) = open.pop() {
+        if distance > distances.distances[state] {
+            continue;
+        }
+        if stop(state) {
+            return (distances, Some(state));
+        }
+        for (next, cost) in successors(state) {
+            let next_distance = distance + cost;
+            if next_distance < distances.distances[next] {
+                distances.distances[next] = next_distance;
+                distances.parents[next] = state;
+                open.push(next_distance, next);
+            }
+        }
+    }
+    (distances, None)
+}
+
+/// The distance to every state reachable from any of the starts.
+pub fn dijkstra_all<I>(
+    len: usize,
+    starts: impl IntoIterator<Item = usize>,
+    successors: impl FnMut(usize) -> I,
+) -> Distances
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    run(len, starts, successors, |_| false).0
+}
+
+/// A shortest path from `start` to any state accepted by `success`, with its cost.
+pub fn dijkstra<I>(
+    len: usize,
+    start: usize,
+    successors: impl FnMut(usize) -> I,
+    success: impl FnMut(usize) -> bool,
+) -> Option<(Vec<usize>, usize)>
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    let (distances, end) = run(len, [start], successors, success);
+    let end = end?;
+    Some((distances.path(end)?, distances.distances[end]))
+}
+
+/// A shortest path from `start` to `goal`, searching forwards from the start and
+/// backwards from the goal at the same time.
+///
+/// `predecessors` must list, for each state, the states with an edge to it and the
+/// edge costs.
+pub fn bidirectional<I, J>(
+    len: usize,
+    start: usize,
+    goal: usize,
+    mut successors: impl FnMut(usize) -> I,
+    mut predecessors: impl FnMut(usize) -> J,
+) -> Option<(Vec<usize>, usize)>
+where
+    I: IntoIterator<Item = (usize, usize)>,
+    J: IntoIterator<Item = (usize, usize)>,
+{
+    let mut sides = [Distances::new(len), Distances::new(len)];
+    let mut open = [RadixHeap::new(), RadixHeap::new()];
+    for (side, state) in [start, goal].into_iter().enumerate() {
+        sides[side].distances[state] = 0;
+        open[side].push(0, state);
+    }
+    let mut best = if start == goal {
+        Some((0, start))
+    } else {
+        None
+    };
+
+    while let (Some(forward), Some(backward)) = (open[0].min_key(), open[1].min_key()) {
+        if best.is_some_and(|(cost, _)| forward + backward >= cost) {
+            break;
+        }
+        let side = usize::from(open[1].len() < open[0].len());
+        let Some((distance, state)) = open[side].pop() else {
+            break;
+        };
+        if distance > sides[side].distances[state] {
+            continue;
+        }
+        let edges = if side == 0 {
+            successors(state).into_iter().collect::<Vec<_>>()
+        } else {
+            predecessors(state).into_iter().collect()
+        };
+        for (next, cost) in edges {
+            let next_distance = distance + cost;
+            if next_distance < sides[side].distances[next] {
+                sides[side].distances[next] = next_distance;
+                sides[side].parents[next] = state;
+                open[side].push(next_distance, next);
+            }
+            let other = sides[1 - side].distances[next];
+            if other != NONE && best.is_none_or(|(cost, _)| next_distance + other < cost) {
+                best = Some((next_distance + other, next));
+            }
+        }
+    }
+
+    let (cost, meeting) = best?;
+    let mut path = sides[0].path(meeting)?;
+    let mut back = sides[1].path(meeting)?;
+    back.pop();
+    path.extend(back.into_iter().rev());
+    Some((path, cost))
+}
+
+/// Every shortest path from `start` to a goal, generated lazily.
+///
+/// `from` holds the distances from `start` and `to` the distances to the nearest
+/// goal, found by searching backwards from the goals. Paths end at the first goal
+/// they reach. All costs must be positive.
+pub fn shortest_paths<I>(
+    start: usize,
+    from: Distances,
+    to: Distances,
+    successors: impl FnMut(usize) -> I,
+) -> impl Iterator<Item = Vec<usize>>
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    ShortestPaths {
+        best: to.get(start),
+        start: Some(start),
+        from,
+        to,
+        successors,
+        stack: vec![],
+    }
+}
+
+struct ShortestPaths<F> {
+    best: Option<usize>,
+    start: Option<usize>,
+    from: Distances,
+    to: Distances,
+    successors: F,
+    /// The current path, with the unexplored next states from each state on it.
+    stack: Vec<(usize, Vec<usize>)>,
+}
+
+impl<F, I> ShortestPaths<F>
+where
+    F: FnMut(usize) -> I,
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    /// The states after `state` on a shortest path.
+    fn next_states(&mut self, state: usize) -> Vec<usize> {
+        let (Some(best), Some(distance)) = (self.best, self.from.get(state)) else {
+            return vec![];
+        };
+        let mut next = (self.successors)(state)
+            .into_iter()
+            .filter(|&(next, cost)| {
+                self.from.get(next) == Some(distance + cost)
+                    && best
+                        .checked_sub(distance + cost)
+                        .is_some_and(|rest| self.to.get(next) == Some(rest))
+            })
+            .map(|(next, _)| next)
+            .collect::<Vec<_>>();
+        next.reverse();
+        next
+    }
+
+    fn current_path(&self, last: usize) -> Vec<usize> {
+        let mut path = self
+            .stack
+            .iter()
+            .map(|&(state, _)| state)
+            .collect::<Vec<_>>();
+        path.push(last);
+        path
+    }
+}
+
+impl<F, I> Iterator for ShortestPaths<F>
+where
+    F: FnMut(usize) -> I,
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if let Some(start) = self.start.take() {
+            self.best?;
+            if self.to.get(start) == Some(0) {
+                return Some(vec![start]);
+            }
+            let next = self.next_states(start);
+            self.stack.push((start, next));
+        }
+        while let Some((_, candidates)) = self.stack.last_mut() {
+            let Some(state) = candidates.pop() else {
+                self.stack.pop();
+                continue;
+            };
+            if self.to.get(state) == Some(0) {
+                return Some(self.current_path(state));
+            }
+            let next = self.next_states(state);
+            self.stack.push((state, next));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Grid, Point};
+
+    const MAZE: &str = "\
+        ...#...\n\
+        .#.#.#.\n\
+        .#...#.\n\
+        .####..\n\
+        .......";
+
+    fn neighbours(maze: &Grid<u8>, state: usize) -> Vec<(usize, usize)> {
+        maze.neighbours(maze.point_of(state))
+            .filter(|(_, &cell)| cell != b'#')
+            .filter_map(|(point, _)| Some((maze.index_of(point)?, 1)))
+            .collect()
+    }
+
+    #[test]
+    fn radix_heap() {
+        let mut heap = RadixHeap::new();
+        for key in [5, 3, 8, 3, 1000, 0] {
+            heap.push(key, key * 10);
+        }
+        assert_eq!(heap.len(), 6);
+        assert_eq!(heap.min_key(), Some(0));
+        assert_eq!(heap.pop(), Some((0, 0)));
+        assert_eq!(heap.pop().map(|(key, _)| key), Some(3));
+        heap.push(4, 40);
+        let keys = std::iter::from_fn(|| heap.pop()).map(|(key, _)| key);
+        assert_eq!(keys.collect::<Vec<_>>(), [3, 4, 5, 8, 1000]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn radix_heap_decreasing_key() {
+        let mut heap = RadixHeap::new();
+        heap.push(5, ());
+        heap.pop();
+        heap.push(4, ());
+    }
+
+    #[test]
+    fn searches() {
+        let maze = Grid::parse(MAZE).unwrap();
+        let start = maze.index_of(Point::new(0, 0)).unwrap();
+        let goal = maze.index_of(Point::new(0, 6)).unwrap();
+        let successors = |state| neighbours(&maze, state);
+
+        let (path, cost) = dijkstra(maze.len(), start, successors, |state| state == goal).unwrap();
+        assert_eq!(cost, 10);
+        assert_eq!(path.len(), 11);
+        assert_eq!((path[0], path[10]), (start, goal));
+
+        let (path, cost) = bidirectional(maze.len(), start, goal, successors, successors).unwrap();
+        assert_eq!(cost, 10);
+        assert_eq!((path.len(), path[0], path[10]), (11, start, goal));
+        assert!(path
+            .windows(2)
+            .all(|pair| successors(pair[0]).contains(&(pair[1], 1))));
+        assert_eq!(
+            bidirectional(maze.len(), start, start, successors, successors),
+            Some((vec![start], 0))
+        );
+
+        let distances = dijkstra_all(maze.len(), [start], successors);
+        assert_eq!(distances.get(goal), Some(10));
+        assert_eq!(
+            distances.get(maze.index_of(Point::new(4, 6)).unwrap()),
+            Some(10)
+        );
+        assert_eq!(
+            distances.get(maze.index_of(Point::new(0, 3)).unwrap()),
+            None
+        );
+        assert_eq!(distances.iter().count(), 25);
+        assert_eq!(distances.path(start), Some(vec![start]));
+    }
+
+    #[test]
+    fn unreachable() {
+        let maze = Grid::parse("..#..").unwrap();
+        let successors = |state| neighbours(&maze, state);
+        assert_eq!(
+            dijkstra(maze.len(), 0, successors, |state| state == 4),
+            None
+        );
+        assert_eq!(
+            bidirectional(maze.len(), 0, 4, successors, successors),
+            None
+        );
+        let from = dijkstra_all(maze.len(), [0], successors);
+        let to = dijkstra_all(maze.len(), [4], successors);
+        assert_eq!(shortest_paths(0, from, to, successors).count(), 0);
+    }
+
+    #[test]
+    fn all_shortest_paths() {
+        let maze = Grid::parse(MAZE).unwrap();
+        let start = maze.index_of(Point::new(0, 0)).unwrap();
+        let goal = maze.index_of(Point::new(3, 6)).unwrap();
+        let successors = |state| neighbours(&maze, state);
+        let from = dijkstra_all(maze.len(), [start], successors);
+        let to = dijkstra_all(maze.len(), [goal], successors);
+        let mut paths = shortest_paths(start, from, to, successors).collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(paths.len(), 2);
+        assert!(paths
+            .iter()
+            .all(|path| path.len() == 12 && path[11] == goal));
+        assert_ne!(paths[0], paths[1]);
+    }
+}
@@ -0,0 +1,158 @@
+use aoc_common::input::Source;
+use thiserror::Error;
+
+pub const USAGE: &str = "\
+Usage: aoc run [--day <DAY>] [--part <PART>] [--input <FILE>]
+
+Options:
+  --day <DAY>     Only run the given day (1-25)
+  --part <PART>   Only run the given part (1 or 2)
+  --input <FILE>  Read the puzzle input from FILE, or from stdin if FILE is `-`.
+                  Requires --day.
+
+Without --input, each day's input is read from the directory named by
+AOC_INPUT_DIR (as dayNN.txt or dayNN/input.txt) if it is set, or else the
+input compiled into the day's crate is used.";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("No command given")]
+    MissingCommand,
+    #[error("Unknown command: {0}")]
+    UnknownCommand(String),
+    #[error("Unknown option: {0}")]
+    UnknownOption(String),
+    #[error("Missing value for {0}")]
+    MissingValue(String),
+    #[error("Invalid day: {0}")]
+    InvalidDay(String),
+    #[error("Invalid part: {0}")]
+    InvalidPart(String),
+    #[error("--input can only be used together with --day")]
+    InputWithoutDay,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(RunArgs),
+    Help,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RunArgs {
+    pub day: Option<u8>,
+    pub part: Option<u8>,
+    pub input: Option<Source>,
+}
+
+pub fn parse<I>(args: I) -> Result<Command, Error>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("run") => parse_run(args).map(Command::Run),
+        Some("help" | "--help" | "-h") => Ok(Command::Help),
+        Some(command) => Err(Error::UnknownCommand(command.to_string())),
+        None => Err(Error::MissingCommand),
+    }
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<RunArgs, Error> {
+    let mut result = RunArgs::default();
+    while let Some(option) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| Error::MissingValue(option.clone()))
+        };
+        match option.as_str() {
+            "--day" => {
+                let day = value()?;
+                result.day = Some(match day.parse() {
+                    Ok(day @ 1..=25) => day,
+                    _ => return Err(Error::InvalidDay(day)),
+                });
+            }
+            "--part" => {
+                let part = value()?;
+                result.part = Some(match part.parse() {
+                    Ok(part @ 1..=2) => part,
+                    _ => return Err(Error::InvalidPart(part)),
+                });
+            }
+            "--input" => {
+                result.input = Some(Source::parse(&value()?));
+            }
+            _ => return Err(Error::UnknownOption(option)),
+        }
+    }
+    if result.input.is_some() && result.day.is_none() {
+        return Err(Error::InputWithoutDay);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(args: &str) -> Result<Command, Error> {
+        parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn run_all() {
+        assert_eq!(parse_str("run"), Ok(Command::Run(RunArgs::default())));
+    }
+
+    #[test]
+    fn run_one() {
+        let expected = RunArgs {
+            day: Some(7),
+            part: Some(2),
+            input: Some(Source::File("input.txt".into())),
+        };
+        assert_eq!(
+            parse_str("run --day 7 --part 2 --input input.txt"),
+            Ok(Command::Run(expected))
+        );
+    }
+
+    #[test]
+    fn stdin() {
+        let expected = RunArgs {
+            day: Some(1),
+            part: None,
+            input: Some(Source::Stdin),
+        };
+        assert_eq!(
+            parse_str("run --input - --day 1"),
+            Ok(Command::Run(expected))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_str(""), Err(Error::MissingCommand));
+        assert_eq!(
+            parse_str("walk"),
+            Err(Error::UnknownCommand("walk".to_string()))
+        );
+        assert_eq!(
+            parse_str("run --day 26"),
+            Err(Error::InvalidDay("26".to_string()))
+        );
+        assert_eq!(
+            parse_str("run --part 3"),
+            Err(Error::InvalidPart("3".to_string()))
+        );
+        assert_eq!(
+            parse_str("run --day"),
+            Err(Error::MissingValue("--day".to_string()))
+        );
+        assert_eq!(
+            parse_str("run --input input.txt"),
+            Err(Error::InputWithoutDay)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use aoc_common::Solution;
+
+/// The solutions for one day together with the input compiled into its crate.
+pub struct Day {
+    pub input: &'static str,
+    pub solutions: &'static [&'static dyn Solution],
+}
+
+pub static DAYS: [Day; 25] = [
+    Day {
+        input: day01::INPUT,
+        solutions: &day01::SOLUTIONS,
+    },
+    Day {
+        input: day02::INPUT,
+        solutions: &day02::SOLUTIONS,
+    },
+    Day {
+        input: day03::INPUT,
+        solutions: &day03::SOLUTIONS,
+    },
+    Day {
+        input: day04::INPUT,
+        solutions: &day04::SOLUTIONS,
+    },
+    Day {
+        input: day05::INPUT,
+        solutions: &day05::SOLUTIONS,
+    },
+    Day {
+        input: day06::INPUT,
+        solutions: &day06::SOLUTIONS,
+    },
+    Day {
+        input: day07::INPUT,
+        solutions: &day07::SOLUTIONS,
+    },
+    Day {
+        input: day08::INPUT,
+        solutions: &day08::SOLUTIONS,
+    },
+    Day {
+        input: day09::INPUT,
+        solutions: &day09::SOLUTIONS,
+    },
+    Day {
+        input: day10::INPUT,
+        solutions: &day10::SOLUTIONS,
+    },
+    Day {
+        input: day11::INPUT,
+        solutions: &day11::SOLUTIONS,
+    },
+    Day {
+        input: day12::INPUT,
+        solutions: &day12::SOLUTIONS,
+    },
+    Day {
+        input: day13::INPUT,
+        solutions: &day13::SOLUTIONS,
+    },
+    Day {
+        input: day14::INPUT,
+        solutions: &day14::SOLUTIONS,
+    },
+    Day {
+        input: day15::INPUT,
+        solutions: &day15::SOLUTIONS,
+    },
+    Day {
+        input: day16::INPUT,
+        solutions: &day16::SOLUTIONS,
+    },
+    Day {
+        input: day17::INPUT,
+        solutions: &day17::SOLUTIONS,
+    },
+    Day {
+        input: day18::INPUT,
+        solutions: &day18::SOLUTIONS,
+    },
+    Day {
+        input: day19::INPUT,
+        solutions: &day19::SOLUTIONS,
+    },
+    Day {
+        input: day20::INPUT,
+        solutions: &day20::SOLUTIONS,
+    },
+    Day {
+        input: day21::INPUT,
+        solutions: &day21::SOLUTIONS,
+    },
+    Day {
+        input: day22::INPUT,
+        solutions: &day22::SOLUTIONS,
+    },
+    Day {
+        input: day23::INPUT,
+        solutions: &day23::SOLUTIONS,
+    },
+    Day {
+        input: day24::INPUT,
+        solutions: &day24::SOLUTIONS,
+    },
+    Day {
+        input: day25::INPUT,
+        solutions: &day25::SOLUTIONS,
+    },
+];
+
+/// Iterates over every registered solution in day and part order.
+pub fn solutions() -> impl Iterator<Item = &'static dyn Solution> {
+    DAYS.iter().flat_map(|day| day.solutions.iter().copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_order() {
+        let expected = (1..=25).flat_map(|day| [(day, 1), (day, 2)]);
+        assert!(solutions()
+            .map(|solution| (solution.day(), solution.part()))
+            .eq(expected));
+    }
+}
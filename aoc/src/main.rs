@@ -0,0 +1,79 @@
+mod args;
+
+use aoc::DAYS;
+use aoc_common::input::{self, Source};
+use args::{Command, RunArgs};
+use std::{
+    borrow::Cow,
+    error::Error,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+fn main() -> ExitCode {
+    match args::parse(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => match run(&args) {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::FAILURE,
+            Err(error) => {
+                eprintln!("Error: {error}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(Command::Help) => {
+            println!("{}", args::USAGE);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("Error: {error}\n\n{}", args::USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs the selected solvers, returning `false` if any of them failed.
+fn run(args: &RunArgs) -> Result<bool, Box<dyn Error>> {
+    let input = args.input.as_ref().map(Source::read).transpose()?;
+
+    let mut success = true;
+    let mut total = Duration::ZERO;
+    let mut count = 0;
+    for (day, solutions) in (1..).zip(&DAYS) {
+        if args.day.is_some_and(|selected| selected != day) {
+            continue;
+        }
+        let day_input = match &input {
+            Some(input) => Cow::Borrowed(input.as_str()),
+            None => match input::from_env(day, solutions.input) {
+                Ok(input) => input,
+                Err(error) => {
+                    println!("Day {day:>2}: Error: {error}");
+                    success = false;
+                    continue;
+                }
+            },
+        };
+        for solution in solutions.solutions {
+            let part = solution.part();
+            if args.part.is_some_and(|selected| selected != part) {
+                continue;
+            }
+            let start = Instant::now();
+            let result = solution.solve(&day_input);
+            let elapsed = start.elapsed();
+            match result {
+                Ok(answer) => println!("Day {day:>2} part {part}: {answer} ({elapsed:.2?})"),
+                Err(error) => {
+                    println!("Day {day:>2} part {part}: Error: {error} ({elapsed:.2?})");
+                    success = false;
+                }
+            }
+            total += elapsed;
+            count += 1;
+        }
+    }
+    if count > 1 {
+        println!("Total: {total:.2?}");
+    }
+    Ok(success)
+}
@@ -1,6 +1,8 @@
+use aoc_common::input;
 use day02::{part2, INPUT};
 
-fn main() -> Result<(), part2::Error> {
-    println!("{:?}", part2::solve(INPUT));
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let input = input::load(2, INPUT)?;
+    println!("{:?}", part2::solve(&input)?);
     Ok(())
 }
@@ -23,20 +23,8 @@ fn is_safe(line: &str) -> Result<bool, Error> {
     {
         let diff = diff?;
         match diff {
-            1..=3 => {
-                if prev >= 0 {
-                    prev = diff;
-                } else {
-                    return Ok(false);
-                }
-            }
-            -3..=-1 => {
-                if prev <= 0 {
-                    prev = diff;
-                } else {
-                    return Ok(false);
-                }
-            }
+            1..=3 if prev >= 0 => prev = diff,
+            -3..=-1 if prev <= 0 => prev = diff,
             _ => return Ok(false),
         }
     }
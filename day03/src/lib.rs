@@ -1,3 +1,5 @@
+use aoc_common::{Solution, Solver};
+
 pub mod part1;
 pub mod part2;
 
@@ -5,3 +7,8 @@ pub mod part2;
 pub const INPUT: &str = include_str!("../input.txt");
 #[cfg(not(input_txt))]
 pub const INPUT: &str = include_str!("../example.txt");
+
+pub static SOLUTIONS: [&dyn Solution; 2] = [
+    &Solver::new(3, 1, part1::solve),
+    &Solver::new(3, 2, part2::solve),
+];
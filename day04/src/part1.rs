@@ -1,70 +1,30 @@
+use aoc_grid::{Grid, Point};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
-pub enum Error {}
-
-const DIRECTIONS: [(isize, isize); 8] = [
-    (-1, -1),
-    (-1, 0),
-    (-1, 1),
-    (0, -1),
-    (0, 1),
-    (1, -1),
-    (1, 0),
-    (1, 1),
-];
+pub enum Error {
+    #[error("Invalid grid: {0}")]
+    InvalidGrid(#[from] aoc_grid::Error),
+}
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let grid = input.lines().map(|x| x.as_bytes()).collect::<Vec<_>>();
-    let cols = 0..grid[0].len();
-    let mut xmas_count = 0;
-
-    for start_row in 0..grid.len() {
-        for start_col in cols.clone() {
-            if grid[start_row][start_col] == b'X' {
-                for (diff_row, diff_col) in DIRECTIONS {
-                    if check_word(b"MAS", &grid, start_row, start_col, diff_row, diff_col) {
-                        xmas_count += 1;
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(xmas_count)
+    let grid = Grid::parse(input)?;
+
+    Ok(grid
+        .find_all(b'X')
+        .map(|start| {
+            Point::SURROUNDING
+                .into_iter()
+                .filter(|&step| check_word(b"MAS", &grid, start, step))
+                .count()
+        })
+        .sum())
 }
 
-fn check_word(
-    word: &[u8],
-    grid: &[&[u8]],
-    start_row: usize,
-    start_col: usize,
-    diff_row: isize,
-    diff_col: isize,
-) -> bool {
-    let mut rows =
-        std::iter::successors(Some(start_row), |r| r.checked_add_signed(diff_row)).skip(1);
-    let mut cols =
-        std::iter::successors(Some(start_col), |c| c.checked_add_signed(diff_col)).skip(1);
-
-    for word_char in word {
-        let Some(row) = rows.next() else {
-            return false;
-        };
-        let Some(col) = cols.next() else {
-            return false;
-        };
-        let Some(row_data) = grid.get(row) else {
-            return false;
-        };
-        let Some(grid_char) = row_data.get(col) else {
-            return false;
-        };
-        if grid_char != word_char {
-            return false;
-        }
-    }
-    true
+fn check_word(word: &[u8], grid: &Grid<u8>, start: Point, step: Point) -> bool {
+    word.iter()
+        .zip(1..)
+        .all(|(word_char, distance)| grid.get(start + step * distance) == Some(word_char))
 }
 
 #[cfg(test)]
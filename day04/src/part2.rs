@@ -1,30 +1,36 @@
+use aoc_grid::{Grid, Point};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
-pub enum Error {}
+pub enum Error {
+    #[error("Invalid grid: {0}")]
+    InvalidGrid(#[from] aoc_grid::Error),
+}
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let grid = input.lines().map(|x| x.as_bytes()).collect::<Vec<_>>();
-    let start_cols = 1..grid[0].len() - 1;
-    let mut xmas_count = 0;
-
-    for row in 1..grid.len() - 1 {
-        for col in start_cols.clone() {
-            if grid[row][col] == b'A' {
-                match (grid[row - 1][col - 1], grid[row + 1][col + 1]) {
-                    (b'M', b'S') | (b'S', b'M') => (),
-                    _ => continue,
-                }
-                match (grid[row - 1][col + 1], grid[row + 1][col - 1]) {
-                    (b'M', b'S') | (b'S', b'M') => (),
-                    _ => continue,
-                }
-                xmas_count += 1
-            }
-        }
-    }
+    let grid = Grid::parse(input)?;
+
+    Ok(grid
+        .find_all(b'A')
+        .filter(|&centre| {
+            is_mas(
+                &grid,
+                centre + Point::new(-1, -1),
+                centre + Point::new(1, 1),
+            ) && is_mas(
+                &grid,
+                centre + Point::new(-1, 1),
+                centre + Point::new(1, -1),
+            )
+        })
+        .count())
+}
 
-    Ok(xmas_count)
+fn is_mas(grid: &Grid<u8>, a: Point, b: Point) -> bool {
+    matches!(
+        (grid.get(a), grid.get(b)),
+        (Some(b'M'), Some(b'S')) | (Some(b'S'), Some(b'M'))
+    )
 }
 
 #[cfg(test)]
@@ -1,15 +1,18 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use aoc_common::bench;
+use criterion::{black_box, Criterion};
 use day05::{part1, part2, INPUT};
 
-fn criterion_benchmark(c: &mut Criterion) {
+fn criterion_benchmark(c: &mut Criterion, input: &str) {
     c.bench_function("solve day05 part1", |b| {
-        b.iter(|| part1::solve(black_box(INPUT)));
+        b.iter(|| part1::solve(black_box(input)));
     });
 
     c.bench_function("solve day05 part2", |b| {
-        b.iter(|| part2::solve(black_box(INPUT)));
+        b.iter(|| part2::solve(black_box(input)));
     });
 }
 
-criterion_group!(benches, criterion_benchmark);
-criterion_main!(benches);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    bench::run(5, INPUT, criterion_benchmark)?;
+    Ok(())
+}
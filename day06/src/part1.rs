@@ -1,11 +1,17 @@
+use aoc_grid::{Direction, Grid, Point};
 use std::collections::HashSet;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
-pub enum Error {}
+pub enum Error {
+    #[error("Invalid map: {0}")]
+    InvalidMap(#[from] aoc_grid::Error),
+    #[error("Guard not found")]
+    GuardNotFound,
+}
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let (map, start) = parse_input(input);
+    let (map, start) = parse_input(input)?;
     let mut visited = HashSet::from([start]);
     let mut guard = Guard {
         location: start,
@@ -20,99 +26,33 @@ pub fn solve(input: &str) -> Result<usize, Error> {
     Ok(visited.len())
 }
 
-fn parse_input(input: &str) -> (Map, Location) {
-    let mut obstructions = HashSet::new();
-    let mut width = 0;
-    let mut height = 0;
-    let mut start = Location { row: 0, column: 0 };
-    for (row, line) in input.lines().enumerate() {
-        height += 1;
-        width = width.max(line.len() as i16);
-        for (column, c) in line.chars().enumerate() {
-            if c == '#' {
-                obstructions.insert(Location {
-                    row: row as i16,
-                    column: column as i16,
-                });
-            }
-            if c == '^' {
-                start = Location {
-                    row: row as i16,
-                    column: column as i16,
-                };
-            }
-        }
-    }
-    (
-        Map {
-            width,
-            height,
-            obstructions,
-        },
-        start,
-    )
-}
-
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
-struct Location {
-    row: i16,
-    column: i16,
-}
-
-struct Map {
-    width: i16,
-    height: i16,
-    obstructions: HashSet<Location>,
-}
-
-#[derive(Clone, Copy)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
+fn parse_input(input: &str) -> Result<(Grid<u8>, Point), Error> {
+    let map = Grid::parse(input)?;
+    let start = map.find(b'^').ok_or(Error::GuardNotFound)?;
+    Ok((map, start))
 }
 
 #[derive(Clone, Copy)]
 struct Guard {
-    location: Location,
+    location: Point,
     direction: Direction,
 }
 
 impl Guard {
-    fn next(&self, map: &Map) -> Option<Self> {
-        let mut next = *self;
-        next.move_forward();
-        if (0..map.height).contains(&next.location.row)
-            && (0..map.width).contains(&next.location.column)
-        {
-            if map.obstructions.contains(&next.location) {
-                next.location = self.location;
-                next.turn_right();
-            }
-            Some(next)
+    fn next(&self, map: &Grid<u8>) -> Option<Self> {
+        let ahead = self.location.step(self.direction);
+        if *map.get(ahead)? == b'#' {
+            Some(Guard {
+                direction: self.direction.turn_right(),
+                ..*self
+            })
         } else {
-            None
+            Some(Guard {
+                location: ahead,
+                ..*self
+            })
         }
     }
-
-    fn move_forward(&mut self) {
-        match self.direction {
-            Direction::North => self.location.row -= 1,
-            Direction::East => self.location.column += 1,
-            Direction::South => self.location.row += 1,
-            Direction::West => self.location.column -= 1,
-        }
-    }
-
-    fn turn_right(&mut self) {
-        self.direction = match self.direction {
-            Direction::North => Direction::East,
-            Direction::East => Direction::South,
-            Direction::South => Direction::West,
-            Direction::West => Direction::North,
-        };
-    }
 }
 
 #[cfg(test)]
@@ -1,83 +1,55 @@
+use aoc_grid::{Grid, Point};
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
-pub enum Error {}
+pub enum Error {
+    #[error("Invalid map: {0}")]
+    InvalidMap(#[from] aoc_grid::Error),
+}
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let map = Map::parse(input);
+    let map = Map::parse(input)?;
 
     Ok(map
         .antennas
-        .iter()
-        .flat_map(|(_, locations)| {
+        .values()
+        .flat_map(|locations| {
             locations
                 .iter()
                 .tuple_combinations()
                 .flat_map(|(a, b)| antinodes(a, b))
         })
         .filter(|location| map.contains(*location))
-        .collect::<HashSet<Location>>()
+        .collect::<HashSet<Point>>()
         .len())
 }
 
-fn antinodes(a: &Location, b: &Location) -> [Location; 2] {
-    let diff_row = a.row - b.row;
-    let diff_column = a.column - b.column;
-    [
-        Location {
-            row: a.row + diff_row,
-            column: a.column + diff_column,
-        },
-        Location {
-            row: b.row - diff_row,
-            column: b.column - diff_column,
-        },
-    ]
-}
-
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
-struct Location {
-    row: i16,
-    column: i16,
+fn antinodes(a: &Point, b: &Point) -> [Point; 2] {
+    let diff = *a - *b;
+    [*a + diff, *b - diff]
 }
 
 struct Map {
-    width: i16,
-    height: i16,
-    antennas: HashMap<char, Vec<Location>>,
+    grid: Grid<u8>,
+    antennas: HashMap<u8, Vec<Point>>,
 }
 
 impl Map {
-    fn parse(input: &str) -> Self {
-        let mut antennas: HashMap<char, Vec<Location>> = HashMap::new();
-        let mut width = 0;
-        let mut height = 0;
-        for (row, line) in input.lines().enumerate() {
-            height += 1;
-            width = width.max(line.len() as i16);
-            for (column, c) in line.chars().enumerate() {
-                if c != '.' {
-                    antennas.entry(c).or_default().push(Location {
-                        row: row as i16,
-                        column: column as i16,
-                    });
-                }
+    fn parse(input: &str) -> Result<Self, Error> {
+        let grid = Grid::parse(input)?;
+        let mut antennas: HashMap<u8, Vec<Point>> = HashMap::new();
+        for (location, &c) in grid.iter() {
+            if c != b'.' {
+                antennas.entry(c).or_default().push(location);
             }
         }
-        Self {
-            width,
-            height,
-            antennas,
-        }
+        Ok(Self { grid, antennas })
     }
 
-    fn contains(&self, location: Location) -> bool {
-        location.row >= 0
-            && location.row < self.height
-            && location.column >= 0
-            && location.column < self.width
+    fn contains(&self, location: Point) -> bool {
+        self.grid.contains(location)
     }
 }
 
@@ -1,117 +1,81 @@
+use aoc_grid::{Grid, Point};
 use itertools::{chain, Itertools};
 use num_integer::Integer;
 use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
-pub enum Error {}
+pub enum Error {
+    #[error("Invalid map: {0}")]
+    InvalidMap(#[from] aoc_grid::Error),
+}
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let map = Map::parse(input);
+    let map = Map::parse(input)?;
 
     Ok(map
         .antennas
-        .iter()
-        .flat_map(|(_, locations)| {
+        .values()
+        .flat_map(|locations| {
             locations
                 .iter()
                 .tuple_combinations()
                 .flat_map(|(a, b)| antinodes(a, b, &map))
         })
-        .collect::<HashSet<Location>>()
+        .collect::<HashSet<Point>>()
         .len())
 }
 
-fn antinodes<'a>(
-    a: &'a Location,
-    b: &'a Location,
-    map: &'a Map,
-) -> impl Iterator<Item = Location> + 'a {
-    let delta_row = a.row - b.row;
-    let delta_column = a.column - b.column;
-    let gcd = delta_row.gcd(&delta_column);
-    let delta_row = delta_row / gcd;
-    let delta_column = delta_column / gcd;
+fn antinodes<'a>(a: &'a Point, b: &'a Point, map: &'a Map) -> impl Iterator<Item = Point> + 'a {
+    let delta = *a - *b;
+    let gcd = delta.row.gcd(&delta.column);
+    let delta = Point::new(delta.row / gcd, delta.column / gcd);
 
     chain![
-        between_antinodes(a, b, delta_row, delta_column),
-        node_antinodes(a, map, delta_row, delta_column),
-        node_antinodes(b, map, -delta_row, -delta_column),
+        between_antinodes(a, b, delta),
+        node_antinodes(a, map, delta),
+        node_antinodes(b, map, -delta),
     ]
 }
 
 fn between_antinodes<'a>(
-    a: &'a Location,
-    b: &'a Location,
-    delta_row: i16,
-    delta_column: i16,
-) -> impl Iterator<Item = Location> + 'a {
-    std::iter::successors(Some(*a), move |node| {
-        Some(Location {
-            row: node.row - delta_row,
-            column: node.column - delta_column,
-        })
-    })
-    .skip(1)
-    .take_while(move |location| location != b)
+    a: &'a Point,
+    b: &'a Point,
+    delta: Point,
+) -> impl Iterator<Item = Point> + 'a {
+    std::iter::successors(Some(*a), move |&node| Some(node - delta))
+        .skip(1)
+        .take_while(move |location| location != b)
 }
 
 fn node_antinodes<'a>(
-    node: &'a Location,
+    node: &'a Point,
     map: &'a Map,
-    delta_row: i16,
-    delta_column: i16,
-) -> impl Iterator<Item = Location> + 'a {
-    std::iter::successors(Some(*node), move |node| {
-        Some(Location {
-            row: node.row + delta_row,
-            column: node.column + delta_column,
-        })
-    })
-    .take_while(|location| map.contains(*location))
-}
-
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
-struct Location {
-    row: i16,
-    column: i16,
+    delta: Point,
+) -> impl Iterator<Item = Point> + 'a {
+    std::iter::successors(Some(*node), move |&node| Some(node + delta))
+        .take_while(|location| map.contains(*location))
 }
 
 struct Map {
-    width: i16,
-    height: i16,
-    antennas: HashMap<char, Vec<Location>>,
+    grid: Grid<u8>,
+    antennas: HashMap<u8, Vec<Point>>,
 }
 
 impl Map {
-    fn parse(input: &str) -> Self {
-        let mut antennas: HashMap<char, Vec<Location>> = HashMap::new();
-        let mut width = 0;
-        let mut height = 0;
-        for (row, line) in input.lines().enumerate() {
-            height += 1;
-            width = width.max(line.len() as i16);
-            for (column, c) in line.chars().enumerate() {
-                if c != '.' {
-                    antennas.entry(c).or_default().push(Location {
-                        row: row as i16,
-                        column: column as i16,
-                    });
-                }
+    fn parse(input: &str) -> Result<Self, Error> {
+        let grid = Grid::parse(input)?;
+        let mut antennas: HashMap<u8, Vec<Point>> = HashMap::new();
+        for (location, &c) in grid.iter() {
+            if c != b'.' {
+                antennas.entry(c).or_default().push(location);
             }
         }
-        Self {
-            width,
-            height,
-            antennas,
-        }
+        Ok(Self { grid, antennas })
     }
 
-    fn contains(&self, location: Location) -> bool {
-        location.row >= 0
-            && location.row < self.height
-            && location.column >= 0
-            && location.column < self.width
+    fn contains(&self, location: Point) -> bool {
+        self.grid.contains(location)
     }
 }
 
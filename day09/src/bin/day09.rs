@@ -0,0 +1,33 @@
+use day09::disk::Disk;
+use std::{error::Error, fs};
+
+const USAGE: &str = "\
+Usage: day09 layout <file>
+       day09 steps <file>";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(2);
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let (command, path) = match args.as_slice() {
+        [command, path] => (command.as_str(), path),
+        _ => usage(),
+    };
+    let mut disk = Disk::parse(&fs::read_to_string(path)?)?;
+    match command {
+        "layout" => println!("{}", disk.compact()),
+        "steps" => {
+            println!("{disk}");
+            for id in (0..disk.files().len()).rev() {
+                if disk.move_file(id).is_some() {
+                    println!("{disk}");
+                }
+            }
+        }
+        _ => usage(),
+    }
+    Ok(())
+}
@@ -0,0 +1,273 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    fmt,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Invalid digit in disk map: {0:?}")]
+    InvalidDigit(char),
+}
+
+/// A map digit is at most 9, so no file is longer.
+const MAX_LENGTH: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct File {
+    pub id: usize,
+    pub position: usize,
+    pub length: usize,
+}
+
+/// Files on a disk, with the free gaps between them kept in one min-heap of
+/// positions per gap length. Finding the leftmost gap a file fits in only
+/// looks at the top of each heap from the file's length up.
+#[derive(Debug, Clone)]
+pub struct Disk {
+    files: Vec<File>,
+    /// Length of the gap starting at each position. Heap entries that no
+    /// longer match are stale and skipped.
+    free: BTreeMap<usize, usize>,
+    gaps: [BinaryHeap<Reverse<(usize, usize)>>; MAX_LENGTH + 1],
+    size: usize,
+}
+
+impl Disk {
+    /// Parses a disk map. Gaps either side of an empty file form one gap.
+    pub fn parse(map: &str) -> Result<Self, Error> {
+        let mut disk = Self {
+            files: Vec::with_capacity(map.len() / 2 + 1),
+            free: BTreeMap::new(),
+            gaps: Default::default(),
+            size: 0,
+        };
+        let mut gap = 0;
+        for (i, digit) in map.trim_end().chars().enumerate() {
+            let length = digit.to_digit(10).ok_or(Error::InvalidDigit(digit))? as usize;
+            if i & 1 == 1 {
+                gap += length;
+            } else {
+                if length > 0 {
+                    disk.add_gap(disk.size - gap, gap);
+                    gap = 0;
+                }
+                disk.files.push(File {
+                    id: i / 2,
+                    position: disk.size,
+                    length,
+                });
+            }
+            disk.size += length;
+        }
+        disk.add_gap(disk.size - gap, gap);
+        Ok(disk)
+    }
+
+    /// Files in order of id.
+    pub fn files(&self) -> &[File] {
+        &self.files
+    }
+
+    /// The number of blocks on the disk, used or free.
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Moves a whole file into the leftmost gap before it that is long enough,
+    /// returning its new position, or `None` if no gap fits. The blocks it
+    /// leaves join any free blocks next to them, so files can be moved in any
+    /// order.
+    pub fn move_file(&mut self, id: usize) -> Option<usize> {
+        let file = *self.files.get(id).filter(|file| file.length > 0)?;
+        let (gap, length) = (file.length..=MAX_LENGTH)
+            .filter_map(|bucket| self.leftmost(bucket))
+            .min()
+            .filter(|&(gap, _)| gap < file.position)?;
+        self.free.remove(&gap);
+        self.add_gap(gap + file.length, length - file.length);
+        self.files[id].position = gap;
+        self.free_blocks(file.position, file.length);
+        Some(gap)
+    }
+
+    /// Tries to move each file once, highest id first.
+    pub fn compact(&mut self) -> &mut Self {
+        for id in (0..self.files.len()).rev() {
+            self.move_file(id);
+        }
+        self
+    }
+
+    /// The id of the file in each block, or `None` for free blocks.
+    pub fn blocks(&self) -> Vec<Option<usize>> {
+        let mut blocks = vec![None; self.size];
+        for file in &self.files {
+            blocks[file.position..file.position + file.length].fill(Some(file.id));
+        }
+        blocks
+    }
+
+    pub fn checksum(&self) -> usize {
+        self.files
+            .iter()
+            .map(|file| file.id * (file.position..file.position + file.length).sum::<usize>())
+            .sum()
+    }
+
+    /// The leftmost gap in a heap, dropping stale entries from its top.
+    fn leftmost(&mut self, bucket: usize) -> Option<(usize, usize)> {
+        while let Some(&Reverse((position, length))) = self.gaps[bucket].peek() {
+            if self.free.get(&position) == Some(&length) {
+                return Some((position, length));
+            }
+            self.gaps[bucket].pop();
+        }
+        None
+    }
+
+    /// Frees the blocks a file left, merging them with the gaps either side.
+    fn free_blocks(&mut self, mut position: usize, mut length: usize) {
+        if let Some((&before, &gap)) = self.free.range(..position).next_back() {
+            if before + gap == position {
+                self.free.remove(&before);
+                (position, length) = (before, gap + length);
+            }
+        }
+        if let Some(gap) = self.free.remove(&(position + length)) {
+            length += gap;
+        }
+        self.add_gap(position, length);
+    }
+
+    /// Gaps of `MAX_LENGTH` or more share the last heap, as any file fits them.
+    fn add_gap(&mut self, position: usize, length: usize) {
+        if length > 0 {
+            self.free.insert(position, length);
+            self.gaps[length.min(MAX_LENGTH)].push(Reverse((position, length)));
+        }
+    }
+}
+
+/// Draws the blocks the way the puzzle does, as `00...111...2`. Ids from 10 on
+/// do not fit in one character and are drawn as `#`.
+impl fmt::Display for Disk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let blocks = self
+            .blocks()
+            .into_iter()
+            .map(|block| match block {
+                Some(id) if id < 10 => (b'0' + id as u8) as char,
+                Some(_) => '#',
+                None => '.',
+            })
+            .collect::<String>();
+        f.write_str(&blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let mut disk = Disk::parse(EXAMPLE).unwrap();
+        let mut steps = vec![disk.to_string()];
+        for id in (0..disk.files().len()).rev() {
+            if disk.move_file(id).is_some() {
+                steps.push(disk.to_string());
+            }
+        }
+        assert_eq!(
+            steps,
+            [
+                "00...111...2...333.44.5555.6666.777.888899",
+                "0099.111...2...333.44.5555.6666.777.8888..",
+                "0099.1117772...333.44.5555.6666.....8888..",
+                "0099.111777244.333....5555.6666.....8888..",
+                "00992111777.44.333....5555.6666.....8888..",
+            ]
+        );
+        assert_eq!(disk.checksum(), 2858);
+        assert_eq!(disk.len(), 42);
+        assert_eq!(disk.blocks()[..4], [Some(0), Some(0), Some(9), Some(9)]);
+        assert_eq!(Disk::parse("12x").unwrap_err(), Error::InvalidDigit('x'));
+    }
+
+    /// Moves files block by block in the given order, scanning from the left
+    /// for each one.
+    fn naive(map: &str, order: impl IntoIterator<Item = usize>) -> Vec<Option<usize>> {
+        let mut blocks = Disk::parse(map).unwrap().blocks();
+        for id in order {
+            let Some(start) = blocks.iter().position(|&block| block == Some(id)) else {
+                continue;
+            };
+            let length = blocks[start..]
+                .iter()
+                .take_while(|&&block| block == Some(id))
+                .count();
+            let gap = (0..start).find(|&gap| {
+                gap + length <= start && blocks[gap..gap + length].iter().all(Option::is_none)
+            });
+            if let Some(gap) = gap {
+                blocks[start..start + length].fill(None);
+                blocks[gap..gap + length].fill(Some(id));
+            }
+        }
+        blocks
+    }
+
+    fn random_maps() -> impl Iterator<Item = (String, Vec<usize>)> {
+        let mut seed = 0x0909_u64;
+        let mut random = move |below: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % below
+        };
+        (0..200).map(move |_| {
+            let map = (0..41)
+                .map(|_| char::from(b'0' + random(10) as u8))
+                .collect::<String>();
+            let order = (0..40).map(|_| random(21)).collect();
+            (map, order)
+        })
+    }
+
+    #[test]
+    fn compact() {
+        for (map, _) in random_maps() {
+            let mut disk = Disk::parse(&map).unwrap();
+            let ids = disk.files().len();
+            assert_eq!(
+                disk.compact().blocks(),
+                naive(&map, (0..ids).rev()),
+                "{map}"
+            );
+        }
+    }
+
+    #[test]
+    fn any_order() {
+        let mut disk = Disk::parse("13321").unwrap();
+        assert_eq!(disk.move_file(1), Some(1));
+        assert_eq!(disk.move_file(2), Some(4));
+        assert_eq!(disk.to_string(), "01112.....");
+
+        for (map, order) in random_maps() {
+            let mut disk = Disk::parse(&map).unwrap();
+            for &id in &order {
+                disk.move_file(id);
+            }
+            assert_eq!(disk.blocks(), naive(&map, order), "{map}");
+        }
+    }
+}
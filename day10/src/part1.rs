@@ -1,61 +1,34 @@
+use aoc_grid::{Grid, Point};
 use std::collections::HashSet;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
-pub enum Error {}
+pub enum Error {
+    #[error("Invalid map: {0}")]
+    InvalidMap(#[from] aoc_grid::Error),
+}
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let map = input
-        .lines()
-        .map(|line| line.as_bytes())
-        .collect::<Vec<_>>();
+    let map = Grid::parse(input)?;
 
     Ok(map
-        .iter()
-        .enumerate()
-        .flat_map(|(row, data)| {
-            data.iter().enumerate().filter_map(
-                move |(col, &cell)| {
-                    if cell == b'0' {
-                        Some((row, col))
-                    } else {
-                        None
-                    }
-                },
-            )
-        })
-        .map(|(row, col)| {
+        .find_all(b'0')
+        .map(|start| {
             let mut peaks = HashSet::new();
-            walk_trails(&map, row, col, b'0', &mut peaks);
+            walk_trails(&map, start, b'0', &mut peaks);
             peaks.len()
         })
         .sum())
 }
 
-const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-
-fn walk_trails(
-    map: &[&[u8]],
-    row: usize,
-    col: usize,
-    height: u8,
-    peaks: &mut HashSet<(usize, usize)>,
-) {
+fn walk_trails(map: &Grid<u8>, location: Point, height: u8, peaks: &mut HashSet<Point>) {
     if height == b'9' {
-        peaks.insert((row, col));
+        peaks.insert(location);
     } else {
         let next_height = height + 1;
-        for (dr, dc) in DIRECTIONS {
-            let Some(row) = row.checked_add_signed(dr) else {
-                continue;
-            };
-            let Some(col) = col.checked_add_signed(dc) else {
-                continue;
-            };
-            let Some(data) = map.get(row) else { continue };
-            let Some(cell) = data.get(col) else { continue };
-            if *cell == next_height {
-                walk_trails(map, row, col, next_height, peaks);
+        for (next, &cell) in map.neighbours(location) {
+            if cell == next_height {
+                walk_trails(map, next, next_height, peaks);
             }
         }
     }
@@ -0,0 +1,24 @@
+use day11::stones::{Rules, Stones};
+use std::{error::Error, fs};
+
+const USAGE: &str = "Usage: day11 counts <file> <blinks>";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(2);
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let (path, blinks) = match args.as_slice() {
+        [command, path, blinks] if command == "counts" => (path, blinks.parse::<usize>()?),
+        _ => usage(),
+    };
+    let stones = fs::read_to_string(path)?.parse::<Stones>()?;
+    let stones = Rules::standard().blinks(&stones, blinks)?;
+    for (value, count) in stones.iter() {
+        println!("{value} {count}");
+    }
+    println!("{} stones, {} values", stones.total()?, stones.distinct());
+    Ok(())
+}
@@ -1,85 +1,22 @@
-use rayon::prelude::*;
-use std::collections::HashMap;
+use crate::stones::{self, Rules, Stones};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Invalid input number: {0}")]
-    InvalidInputNumber(#[from] std::num::ParseIntError),
+    #[error("Stones error: {0}")]
+    Stones(#[from] stones::Error),
+    #[error("Stone count too large: {0}")]
+    CountTooLarge(u128),
 }
 
 pub fn solve(input: &str) -> Result<u64, Error> {
-    input
-        .par_split_whitespace()
-        .map(|s| {
-            let mut memo = HashMap::new();
-            Ok(count_stones(s.parse()?, 25, &mut memo))
-        })
-        .try_reduce(|| 0, |a, b| Ok(a + b))
+    count_stones(input, 25)
 }
 
-fn count_stones(stone: u64, blinks: u8, memo: &mut HashMap<(u64, u8), u64>) -> u64 {
-    if blinks == 0 {
-        1
-    } else if let Some(count) = memo.get(&(stone, blinks)) {
-        *count
-    } else if stone == 0 {
-        let count = count_stones(1, blinks - 1, memo);
-        memo.insert((stone, blinks), count);
-        count
-    } else if let Some((a, b)) = split_digits(stone) {
-        let count = count_stones(a, blinks - 1, memo) + count_stones(b, blinks - 1, memo);
-        memo.insert((stone, blinks), count);
-        count
-    } else {
-        let count = count_stones(stone * 2024, blinks - 1, memo);
-        memo.insert((stone, blinks), count);
-        count
-    }
-}
-
-fn split_digits(n: u64) -> Option<(u64, u64)> {
-    if n < 10 {
-        None
-    } else if n < 100 {
-        Some((n / 10, n % 10))
-    } else if n < 1000 {
-        None
-    } else if n < 10000 {
-        Some((n / 100, n % 100))
-    } else if n < 100000 {
-        None
-    } else if n < 1000000 {
-        Some((n / 1000, n % 1000))
-    } else if n < 10000000 {
-        None
-    } else if n < 100000000 {
-        Some((n / 10000, n % 10000))
-    } else if n < 1000000000 {
-        None
-    } else if n < 10000000000 {
-        Some((n / 100000, n % 100000))
-    } else if n < 100000000000 {
-        None
-    } else if n < 1000000000000 {
-        Some((n / 1000000, n % 1000000))
-    } else if n < 10000000000000 {
-        None
-    } else if n < 100000000000000 {
-        Some((n / 10000000, n % 10000000))
-    } else if n < 1000000000000000 {
-        None
-    } else if n < 10000000000000000 {
-        Some((n / 100000000, n % 100000000))
-    } else if n < 100000000000000000 {
-        None
-    } else if n < 1000000000000000000 {
-        Some((n / 1000000000, n % 1000000000))
-    } else if n < 10000000000000000000 {
-        None
-    } else {
-        Some((n / 10000000000, n % 10000000000))
-    }
+fn count_stones(input: &str, blinks: usize) -> Result<u64, Error> {
+    let stones = input.parse::<Stones>()?;
+    let total = Rules::standard().blinks(&stones, blinks)?.total()?;
+    u64::try_from(total).map_err(|_| Error::CountTooLarge(total))
 }
 
 #[cfg(test)]
@@ -1,83 +1,37 @@
-use std::collections::HashMap;
+use crate::stones::{self, Rules, Stones};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Invalid input number: {0}")]
-    InvalidInputNumber(#[from] std::num::ParseIntError),
+    #[error("Stones error: {0}")]
+    Stones(#[from] stones::Error),
+    #[error("Stone count too large: {0}")]
+    CountTooLarge(u128),
 }
 
-pub fn solve(input: &str) -> Result<u64, Error> {
-    let mut memo = HashMap::new();
-
-    input
-        .split_whitespace()
-        .map(|s| Ok::<u64, Error>(count_stones(s.parse()?, 75, &mut memo)))
-        .try_fold(0, |acc, n| Ok(acc + n?))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    pub blinks: usize,
 }
 
-fn count_stones(stone: u64, blinks: u8, memo: &mut HashMap<(u64, u8), u64>) -> u64 {
-    if blinks == 0 {
-        1
-    } else if let Some(count) = memo.get(&(stone, blinks)) {
-        *count
-    } else if stone == 0 {
-        let count = count_stones(1, blinks - 1, memo);
-        memo.insert((stone, blinks), count);
-        count
-    } else if let Some((a, b)) = split_digits(stone) {
-        let count = count_stones(a, blinks - 1, memo) + count_stones(b, blinks - 1, memo);
-        memo.insert((stone, blinks), count);
-        count
-    } else {
-        let count = count_stones(stone * 2024, blinks - 1, memo);
-        memo.insert((stone, blinks), count);
-        count
+impl Default for Params {
+    fn default() -> Self {
+        Self { blinks: 75 }
     }
 }
 
-fn split_digits(n: u64) -> Option<(u64, u64)> {
-    if n < 10 {
-        None
-    } else if n < 100 {
-        Some((n / 10, n % 10))
-    } else if n < 1000 {
-        None
-    } else if n < 10000 {
-        Some((n / 100, n % 100))
-    } else if n < 100000 {
-        None
-    } else if n < 1000000 {
-        Some((n / 1000, n % 1000))
-    } else if n < 10000000 {
-        None
-    } else if n < 100000000 {
-        Some((n / 10000, n % 10000))
-    } else if n < 1000000000 {
-        None
-    } else if n < 10000000000 {
-        Some((n / 100000, n % 100000))
-    } else if n < 100000000000 {
-        None
-    } else if n < 1000000000000 {
-        Some((n / 1000000, n % 1000000))
-    } else if n < 10000000000000 {
-        None
-    } else if n < 100000000000000 {
-        Some((n / 10000000, n % 10000000))
-    } else if n < 1000000000000000 {
-        None
-    } else if n < 10000000000000000 {
-        Some((n / 100000000, n % 100000000))
-    } else if n < 100000000000000000 {
-        None
-    } else if n < 1000000000000000000 {
-        Some((n / 1000000000, n % 1000000000))
-    } else if n < 10000000000000000000 {
-        None
-    } else {
-        Some((n / 10000000000, n % 10000000000))
-    }
+pub fn solve(input: &str) -> Result<u64, Error> {
+    solve_with(input, Params::default())
+}
+
+pub fn solve_with(input: &str, params: Params) -> Result<u64, Error> {
+    count_stones(input, params.blinks)
+}
+
+fn count_stones(input: &str, blinks: usize) -> Result<u64, Error> {
+    let stones = input.parse::<Stones>()?;
+    let total = Rules::standard().blinks(&stones, blinks)?.total()?;
+    u64::try_from(total).map_err(|_| Error::CountTooLarge(total))
 }
 
 #[cfg(test)]
@@ -92,6 +46,12 @@ mod tests {
         assert_eq!(result, 65601038650482);
     }
 
+    #[test]
+    fn example_25_blinks() {
+        let result = solve_with(EXAMPLE, Params { blinks: 25 }).unwrap();
+        assert_eq!(result, 55312);
+    }
+
     #[cfg(input_txt)]
     #[cfg(part2_txt)]
     #[test]
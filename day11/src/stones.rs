@@ -0,0 +1,204 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Invalid input number: {0}")]
+    InvalidInputNumber(#[from] std::num::ParseIntError),
+    #[error("Stone value overflowed when changing stone {0}")]
+    ValueOverflow(u64),
+    #[error("Stone count overflowed")]
+    CountOverflow,
+}
+
+type Predicate = Box<dyn Fn(u64) -> bool + Send + Sync>;
+type Transform = Box<dyn Fn(u64) -> Option<Vec<u64>> + Send + Sync>;
+
+/// Changes a stone into the stones returned by `transform` when `predicate`
+/// holds. The transform returns `None` if a new value would overflow.
+pub struct Rule {
+    predicate: Predicate,
+    transform: Transform,
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rule(..)")
+    }
+}
+
+/// Rules tried in order on every stone at each blink. The first that applies
+/// wins, and a stone no rule applies to stays as it is.
+#[derive(Debug, Default)]
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+
+impl Rules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The puzzle's rules: 0 becomes 1, a stone with an even number of digits
+    /// splits into its two halves, and any other stone is multiplied by 2024.
+    pub fn standard() -> Self {
+        Self::new()
+            .rule(|stone| stone == 0, |_| Some(vec![1]))
+            .rule(
+                |stone| split_digits(stone).is_some(),
+                |stone| split_digits(stone).map(|(a, b)| vec![a, b]),
+            )
+            .rule(
+                |_| true,
+                |stone| stone.checked_mul(2024).map(|stone| vec![stone]),
+            )
+    }
+
+    /// Adds a rule after the existing ones.
+    pub fn rule(
+        mut self,
+        predicate: impl Fn(u64) -> bool + Send + Sync + 'static,
+        transform: impl Fn(u64) -> Option<Vec<u64>> + Send + Sync + 'static,
+    ) -> Self {
+        self.rules.push(Rule {
+            predicate: Box::new(predicate),
+            transform: Box::new(transform),
+        });
+        self
+    }
+
+    /// The stones one stone changes into.
+    pub fn apply(&self, stone: u64) -> Result<Vec<u64>, Error> {
+        match self.rules.iter().find(|rule| (rule.predicate)(stone)) {
+            Some(rule) => (rule.transform)(stone).ok_or(Error::ValueOverflow(stone)),
+            None => Ok(vec![stone]),
+        }
+    }
+
+    /// Changes every stone once, applying the rules to each value only once
+    /// however many stones carry it.
+    pub fn blink(&self, stones: &Stones) -> Result<Stones, Error> {
+        let mut next = Stones::default();
+        for (&stone, &count) in &stones.counts {
+            for value in self.apply(stone)? {
+                next.add(value, count)?;
+            }
+        }
+        Ok(next)
+    }
+
+    pub fn blinks(&self, stones: &Stones, blinks: usize) -> Result<Stones, Error> {
+        let mut stones = stones.clone();
+        for _ in 0..blinks {
+            if stones.counts.is_empty() {
+                break;
+            }
+            stones = self.blink(&stones)?;
+        }
+        Ok(stones)
+    }
+}
+
+/// How many stones carry each value. Order along the line does not affect how
+/// the stones change, so only the counts are kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stones {
+    counts: HashMap<u64, u128>,
+}
+
+impl Stones {
+    pub fn add(&mut self, value: u64, count: u128) -> Result<(), Error> {
+        let total = self.counts.entry(value).or_default();
+        *total = total.checked_add(count).ok_or(Error::CountOverflow)?;
+        Ok(())
+    }
+
+    pub fn count(&self, value: u64) -> u128 {
+        self.counts.get(&value).copied().unwrap_or(0)
+    }
+
+    /// Values and the number of stones carrying them, smallest value first.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u128)> {
+        let mut counts = self
+            .counts
+            .iter()
+            .map(|(&value, &count)| (value, count))
+            .collect::<Vec<_>>();
+        counts.sort_unstable();
+        counts.into_iter()
+    }
+
+    /// The number of different values.
+    pub fn distinct(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn total(&self) -> Result<u128, Error> {
+        self.counts
+            .values()
+            .try_fold(0u128, |total, &count| total.checked_add(count))
+            .ok_or(Error::CountOverflow)
+    }
+}
+
+impl FromStr for Stones {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut stones = Self::default();
+        for value in s.split_whitespace() {
+            stones.add(value.parse()?, 1)?;
+        }
+        Ok(stones)
+    }
+}
+
+/// The two halves of a number with an even number of digits.
+fn split_digits(n: u64) -> Option<(u64, u64)> {
+    let digits = n.checked_ilog10()? + 1;
+    if digits % 2 == 1 {
+        return None;
+    }
+    let half = 10u64.pow(digits / 2);
+    Some((n / half, n % half))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn example() {
+        let rules = Rules::standard();
+        let stones = EXAMPLE.parse::<Stones>().unwrap();
+        let expected = "2097446912 14168 4048 2 0 2 4 40 48 2024 40 48 80 96 2 8 6 7 6 0 3 2";
+        assert_eq!(rules.blinks(&stones, 6), expected.parse());
+        assert_eq!(rules.blinks(&stones, 6).unwrap().count(2), 4);
+        assert_eq!(rules.blinks(&stones, 25).unwrap().total(), Ok(55312));
+        assert_eq!(rules.blinks(&stones, 300), Err(Error::CountOverflow));
+        assert_eq!(split_digits(u64::MAX / 10), None);
+        assert_eq!(split_digits(1000), Some((10, 0)));
+    }
+
+    #[test]
+    fn custom_rules() {
+        // Halve even stones and drop odd ones.
+        let rules = Rules::new()
+            .rule(|stone| stone % 2 == 1, |_| Some(vec![]))
+            .rule(|_| true, |stone| Some(vec![stone / 2, stone / 2]));
+        let stones = "12 7".parse::<Stones>().unwrap();
+        let stones = rules.blinks(&stones, 2).unwrap();
+        assert_eq!(stones.iter().collect::<Vec<_>>(), [(3, 4)]);
+        assert_eq!(rules.blinks(&stones, 10).unwrap().total(), Ok(0));
+
+        let rules = Rules::new().rule(
+            |stone| stone > 5,
+            |stone| stone.checked_mul(1 << 40).map(|stone| vec![stone]),
+        );
+        let stones = "3 6".parse::<Stones>().unwrap();
+        assert_eq!(rules.blink(&stones).unwrap().count(3), 1);
+        assert_eq!(rules.blinks(&stones, 2), Err(Error::ValueOverflow(6 << 40)));
+    }
+}
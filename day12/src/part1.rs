@@ -1,8 +1,12 @@
+use aoc_grid::{Grid, Point};
 use std::cell::Cell;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
-pub enum Error {}
+pub enum Error {
+    #[error("Invalid map: {0}")]
+    InvalidMap(#[from] aoc_grid::Error),
+}
 
 struct Plot {
     plant: u8,
@@ -16,65 +20,37 @@ struct Region {
 }
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let map = input
-        .lines()
-        .map(|line| {
-            line.as_bytes()
-                .iter()
-                .map(|&plant| Plot {
-                    plant,
-                    visited: Cell::new(false),
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+    let map = Grid::parse(input)?.map(|&plant| Plot {
+        plant,
+        visited: Cell::new(false),
+    });
 
     Ok(map
         .iter()
-        .enumerate()
-        .flat_map(|(row, data)| {
-            data.iter()
-                .enumerate()
-                .filter_map(move |(col, plot)| {
-                    if !plot.visited.get() {
-                        Some((row, col, plot))
-                    } else {
-                        None
-                    }
-                })
-                .map(|(row, col, plot)| {
-                    plot.visited.set(true);
-                    let mut region = Region::default();
-                    trace_region(&map, row, col, plot.plant, &mut region);
-                    region
-                })
+        .filter_map(|(location, plot)| {
+            if plot.visited.get() {
+                return None;
+            }
+            plot.visited.set(true);
+            let mut region = Region::default();
+            trace_region(&map, location, plot.plant, &mut region);
+            Some(region)
         })
         .map(|region| region.area * region.perimeter)
         .sum())
 }
 
-const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-
-fn trace_region(map: &Vec<Vec<Plot>>, row: usize, col: usize, plant: u8, region: &mut Region) {
+fn trace_region(map: &Grid<Plot>, location: Point, plant: u8, region: &mut Region) {
     region.area += 1;
-    for (dr, dc) in DIRECTIONS {
-        let Some(row) = row.checked_add_signed(dr) else {
-            region.perimeter += 1;
-            continue;
-        };
-        let Some(col) = col.checked_add_signed(dc) else {
-            region.perimeter += 1;
-            continue;
-        };
-        let Some(plot) = map.get(row).and_then(|row| row.get(col)) else {
-            region.perimeter += 1;
-            continue;
-        };
-        if plot.plant != plant {
-            region.perimeter += 1;
-        } else if !plot.visited.get() {
-            plot.visited.set(true);
-            trace_region(map, row, col, plant, region);
+    for next in location.neighbours() {
+        match map.get(next) {
+            Some(plot) if plot.plant == plant => {
+                if !plot.visited.get() {
+                    plot.visited.set(true);
+                    trace_region(map, next, plant, region);
+                }
+            }
+            _ => region.perimeter += 1,
         }
     }
 }
@@ -1,8 +1,12 @@
+use aoc_grid::{Grid, Point};
 use std::{cell::Cell, collections::HashSet};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
-pub enum Error {}
+pub enum Error {
+    #[error("Invalid map: {0}")]
+    InvalidMap(#[from] aoc_grid::Error),
+}
 
 struct Plot {
     plant: u8,
@@ -16,70 +20,40 @@ struct Region {
 }
 
 pub fn solve(input: &str) -> Result<usize, Error> {
-    let map = input
-        .lines()
-        .map(|line| {
-            line.as_bytes()
-                .iter()
-                .map(|&plant| Plot {
-                    plant,
-                    visited: Cell::new(false),
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+    let map = Grid::parse(input)?.map(|&plant| Plot {
+        plant,
+        visited: Cell::new(false),
+    });
 
     Ok(map
         .iter()
-        .enumerate()
-        .flat_map(|(row, data)| {
-            data.iter()
-                .enumerate()
-                .filter_map(move |(col, plot)| {
-                    if !plot.visited.get() {
-                        Some((row, col, plot))
-                    } else {
-                        None
-                    }
-                })
-                .map(|(row, col, plot)| {
-                    plot.visited.set(true);
-                    trace_region(&map, row, col, plot.plant)
-                })
+        .filter_map(|(location, plot)| {
+            if plot.visited.get() {
+                return None;
+            }
+            plot.visited.set(true);
+            Some(trace_region(&map, location, plot.plant))
         })
         .map(|region| region.area * region.sides)
         .sum())
 }
 
-const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-
-fn trace_region(map: &[Vec<Plot>], row: usize, col: usize, plant: u8) -> Region {
+fn trace_region(map: &Grid<Plot>, start: Point, plant: u8) -> Region {
     let mut region_plots = HashSet::new();
-    let mut stack = vec![(row, col)];
-    let mut max_row = row;
-    let mut max_col = col;
-    let mut min_row = row;
-    let mut min_col = col;
-
-    while let Some((row, col)) = stack.pop() {
-        region_plots.insert((row, col));
-        max_row = max_row.max(row);
-        max_col = max_col.max(col);
-        min_row = min_row.min(row);
-        min_col = min_col.min(col);
-        for (dr, dc) in DIRECTIONS {
-            let Some(row) = row.checked_add_signed(dr) else {
-                continue;
-            };
-            let Some(col) = col.checked_add_signed(dc) else {
-                continue;
-            };
-            let Some(plot) = map.get(row).and_then(|row| row.get(col)) else {
-                continue;
-            };
+    let mut stack = vec![start];
+    let mut max = start;
+    let mut min = start;
+
+    while let Some(location) = stack.pop() {
+        region_plots.insert(location);
+        max.row = max.row.max(location.row);
+        max.column = max.column.max(location.column);
+        min.row = min.row.min(location.row);
+        min.column = min.column.min(location.column);
+        for (next, plot) in map.neighbours(location) {
             if plot.plant == plant && !plot.visited.get() {
                 plot.visited.set(true);
-                stack.push((row, col));
+                stack.push(next);
             }
         }
     }
@@ -89,18 +63,18 @@ fn trace_region(map: &[Vec<Plot>], row: usize, col: usize, plant: u8) -> Region
         sides: 0,
     };
 
-    for row in min_row..=max_row {
+    for row in min.row..=max.row {
         let mut top_side = false;
         let mut bottom_side = false;
-        for col in min_col..=max_col {
-            if region_plots.contains(&(row, col)) {
-                if row > 0 && region_plots.contains(&(row - 1, col)) {
+        for column in min.column..=max.column {
+            if region_plots.contains(&Point::new(row, column)) {
+                if region_plots.contains(&Point::new(row - 1, column)) {
                     top_side = false;
                 } else if !top_side {
                     region.sides += 1;
                     top_side = true;
                 }
-                if region_plots.contains(&(row + 1, col)) {
+                if region_plots.contains(&Point::new(row + 1, column)) {
                     bottom_side = false;
                 } else if !bottom_side {
                     region.sides += 1;
@@ -113,18 +87,18 @@ fn trace_region(map: &[Vec<Plot>], row: usize, col: usize, plant: u8) -> Region
         }
     }
 
-    for col in min_col..=max_col {
+    for column in min.column..=max.column {
         let mut left_side = false;
         let mut right_side = false;
-        for row in min_row..=max_row {
-            if region_plots.contains(&(row, col)) {
-                if col > 0 && region_plots.contains(&(row, col - 1)) {
+        for row in min.row..=max.row {
+            if region_plots.contains(&Point::new(row, column)) {
+                if region_plots.contains(&Point::new(row, column - 1)) {
                     left_side = false;
                 } else if !left_side {
                     region.sides += 1;
                     left_side = true;
                 }
-                if region_plots.contains(&(row, col + 1)) {
+                if region_plots.contains(&Point::new(row, column + 1)) {
                     right_side = false;
                 } else if !right_side {
                     region.sides += 1;
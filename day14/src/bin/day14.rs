@@ -0,0 +1,102 @@
+use day14::{
+    detect,
+    motion::{self, Robot},
+    render::{self, Options},
+};
+use std::{error::Error, fs, io::BufWriter};
+
+const USAGE: &str = "\
+Usage: day14 analyse <file> [<width> <height>]
+       day14 detect <file> [<width> <height>]
+       day14 text <file> <seconds> [<width> <height>]
+       day14 png <file> <seconds> <output> [<width> <height>]
+       day14 pbm <file> <seconds> <output> [<width> <height>]
+       day14 sheet <file> <from> <to> <output> [<width> <height>]";
+
+/// Frames per row of a sprite sheet.
+const SHEET_COLUMNS: usize = 10;
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(2);
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let arity = match args.first().map(String::as_str) {
+        Some("analyse" | "detect") => 2,
+        Some("text") => 3,
+        Some("png" | "pbm") => 4,
+        Some("sheet") => 5,
+        _ => usage(),
+    };
+    if args.len() < arity {
+        usage();
+    }
+    let (width, height) = match &args[arity..] {
+        [] => (101, 103),
+        [width, height] => (width.parse()?, height.parse()?),
+        _ => usage(),
+    };
+    let robots = motion::parse(&fs::read_to_string(&args[1])?)?;
+    match &args[..arity] {
+        [command, _] if command == "analyse" => analyse(&robots, width, height)?,
+        [_, _] => {
+            let seconds = 0..width as usize * height as usize;
+            for report in detect::scan(&robots, &detect::standard(), seconds, width, height) {
+                let first = match report.first {
+                    Some(time) => format!("first fired at {time}"),
+                    None => "never fired".to_string(),
+                };
+                println!(
+                    "{}: best {:.2} at {}, threshold {:.2}, {first}",
+                    report.name, report.best, report.time, report.threshold
+                );
+            }
+        }
+        [_, _, seconds] => {
+            let frame = render::frame(&robots, seconds.parse()?, width, height);
+            print!("{}", render::text(&frame));
+        }
+        [command, _, seconds, output] => {
+            let frame = render::frame(&robots, seconds.parse()?, width, height);
+            let file = BufWriter::new(fs::File::create(output)?);
+            if command == "png" {
+                render::write_png(file, &render::image(&frame), Options::default())?;
+            } else {
+                render::write_pbm(file, &render::image(&frame), Options { scale: 1 })?;
+            }
+        }
+        [_, _, from, to, output] => {
+            let frames = (from.parse()?..to.parse()?)
+                .map(|seconds| render::frame(&robots, seconds, width, height))
+                .collect::<Vec<_>>();
+            let sheet = render::sheet(&frames, SHEET_COLUMNS)?;
+            let file = BufWriter::new(fs::File::create(output)?);
+            render::write_png(file, &sheet, Options { scale: 2 })?;
+        }
+        _ => usage(),
+    }
+    Ok(())
+}
+
+fn analyse(robots: &[Robot], width: i32, height: i32) -> Result<(), Box<dyn Error>> {
+    let analysis = motion::analyse(robots, width, height)?;
+    for (name, axis) in [("x", analysis.x), ("y", analysis.y)] {
+        println!(
+            "{name}: offset {} of {}, variance {:.1}, score {:.1}",
+            axis.offset, axis.period, axis.variance, axis.score
+        );
+    }
+    println!(
+        "time {}, confidence {:.1}",
+        analysis.time, analysis.confidence
+    );
+    if !analysis.has_expected_period() {
+        println!(
+            "warning: pattern repeats every {} seconds, not {}",
+            analysis.period, analysis.expected_period
+        );
+    }
+    Ok(())
+}
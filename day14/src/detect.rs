@@ -0,0 +1,284 @@
+use crate::{motion::Robot, render};
+use aoc_grid::{Grid, Point};
+use std::ops::Range;
+
+/// Decides whether a frame of robot counts, as drawn by `render::frame`, looks
+/// like a picture.
+pub trait Detector {
+    fn name(&self) -> &'static str;
+
+    /// How much the frame looks like a picture, higher meaning more.
+    fn score(&self, frame: &Grid<u8>) -> f64;
+
+    /// The score a frame has to beat to count as a picture.
+    fn threshold(&self) -> f64;
+
+    fn detect(&self, frame: &Grid<u8>) -> bool {
+        self.score(frame) > self.threshold()
+    }
+}
+
+/// Pairs of robots on touching tiles, per robot. This is the heuristic part 2
+/// steps through the seconds with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbours {
+    pub fraction: f64,
+}
+
+impl Default for Neighbours {
+    fn default() -> Self {
+        Self { fraction: 0.7 }
+    }
+}
+
+impl Detector for Neighbours {
+    fn name(&self) -> &'static str {
+        "neighbours"
+    }
+
+    fn score(&self, frame: &Grid<u8>) -> f64 {
+        let mut robots = 0;
+        let mut pairs = 0;
+        for (position, &count) in frame.iter() {
+            robots += count as usize;
+            for next in [position + Point::new(0, 1), position + Point::new(1, 0)] {
+                pairs += count as usize * frame.get(next).copied().unwrap_or(0) as usize;
+            }
+        }
+        if robots == 0 {
+            0.0
+        } else {
+            pairs as f64 / robots as f64
+        }
+    }
+
+    fn threshold(&self) -> f64 {
+        self.fraction
+    }
+}
+
+/// Bits by which the spread of robots over square blocks falls short of an
+/// even spread. Noise falls a little short too, as there are too few robots
+/// to fill every block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entropy {
+    /// Tiles along each side of a block.
+    pub block: usize,
+    pub bits: f64,
+}
+
+impl Default for Entropy {
+    fn default() -> Self {
+        Self {
+            block: 4,
+            bits: 2.0,
+        }
+    }
+}
+
+impl Detector for Entropy {
+    fn name(&self) -> &'static str {
+        "entropy"
+    }
+
+    fn score(&self, frame: &Grid<u8>) -> f64 {
+        let block = self.block.max(1);
+        let columns = frame.width().div_ceil(block);
+        let mut blocks = vec![0usize; columns * frame.height().div_ceil(block)];
+        for (position, &count) in frame.iter() {
+            let (row, column) = (position.row as usize, position.column as usize);
+            blocks[row / block * columns + column / block] += count as usize;
+        }
+        let robots = blocks.iter().sum::<usize>() as f64;
+        if robots == 0.0 {
+            return 0.0;
+        }
+        let entropy = blocks
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f64 / robots;
+                -p * p.log2()
+            })
+            .sum::<f64>();
+        (blocks.len() as f64).log2() - entropy
+    }
+
+    fn threshold(&self) -> f64 {
+        self.bits
+    }
+}
+
+/// The shorter of the longest unbroken row and column of robots, like the
+/// frame drawn around the picture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Border {
+    pub length: usize,
+}
+
+impl Default for Border {
+    fn default() -> Self {
+        Self { length: 10 }
+    }
+}
+
+impl Detector for Border {
+    fn name(&self) -> &'static str {
+        "border"
+    }
+
+    fn score(&self, frame: &Grid<u8>) -> f64 {
+        let across = frame
+            .rows()
+            .map(|row| longest_run(row.iter().copied()))
+            .max()
+            .unwrap_or(0);
+        let down = (0..frame.width() as i32)
+            .map(|column| {
+                longest_run((0..frame.height() as i32).map(|row| frame[Point::new(row, column)]))
+            })
+            .max()
+            .unwrap_or(0);
+        across.min(down) as f64
+    }
+
+    fn threshold(&self) -> f64 {
+        self.length as f64
+    }
+}
+
+/// One of each detector with its default threshold.
+pub fn standard() -> Vec<Box<dyn Detector>> {
+    vec![
+        Box::new(Neighbours::default()),
+        Box::new(Entropy::default()),
+        Box::new(Border::default()),
+    ]
+}
+
+/// How one detector scored over a range of seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Report {
+    pub name: &'static str,
+    pub threshold: f64,
+    /// The highest score and the first second it was reached.
+    pub best: f64,
+    pub time: usize,
+    /// The first second the detector fired, if any.
+    pub first: Option<usize>,
+}
+
+/// Scores every frame in `seconds` with every detector.
+pub fn scan(
+    robots: &[Robot],
+    detectors: &[Box<dyn Detector>],
+    seconds: Range<usize>,
+    width: i32,
+    height: i32,
+) -> Vec<Report> {
+    let mut reports = detectors
+        .iter()
+        .map(|detector| Report {
+            name: detector.name(),
+            threshold: detector.threshold(),
+            best: f64::NEG_INFINITY,
+            time: seconds.start,
+            first: None,
+        })
+        .collect::<Vec<_>>();
+    for time in seconds {
+        let frame = render::frame(robots, time, width, height);
+        for (detector, report) in detectors.iter().zip(&mut reports) {
+            let score = detector.score(&frame);
+            if score > report.best {
+                report.best = score;
+                report.time = time;
+            }
+            if report.first.is_none() && score > report.threshold {
+                report.first = Some(time);
+            }
+        }
+    }
+    reports
+}
+
+/// The first second in `seconds` at which the detector fires.
+pub fn first(
+    robots: &[Robot],
+    detector: &dyn Detector,
+    seconds: Range<usize>,
+    width: i32,
+    height: i32,
+) -> Option<usize> {
+    seconds.into_iter().find(|&time| {
+        let frame = render::frame(robots, time, width, height);
+        detector.detect(&frame)
+    })
+}
+
+fn longest_run(counts: impl Iterator<Item = u8>) -> usize {
+    counts
+        .fold((0, 0), |(longest, run), count| {
+            let run = if count > 0 { run + 1 } else { 0 };
+            (longest.max(run), run)
+        })
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motion;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    fn frame(text: &str) -> Grid<u8> {
+        Grid::parse(text)
+            .unwrap()
+            .map(|&tile| if tile == b'.' { 0 } else { tile - b'0' })
+    }
+
+    #[test]
+    fn scores() {
+        let frame = frame("21..\n.1..\n....\n...1");
+        let neighbours = Neighbours::default();
+        assert_eq!(neighbours.score(&frame), 3.0 / 5.0);
+        assert!(!neighbours.detect(&frame));
+        assert!(Neighbours { fraction: 0.5 }.detect(&frame));
+
+        // Four blocks of four tiles hold 4 and 1 robots.
+        let entropy = Entropy {
+            block: 2,
+            bits: 1.0,
+        };
+        let expected = 2.0 + 0.8 * 0.8f64.log2() + 0.2 * 0.2f64.log2();
+        assert!((entropy.score(&frame) - expected).abs() < 1e-9);
+        assert_eq!(entropy.score(&self::frame("1111\n1111\n1111\n1111")), 0.0);
+        assert_eq!(entropy.score(&self::frame("....\n....")), 0.0);
+
+        let border = Border::default();
+        assert_eq!(border.score(&frame), 2.0);
+        let boxed = "\
+.......
+.11111.
+.1...1.
+.1.1.1.
+.11111.";
+        assert_eq!(border.score(&self::frame(boxed)), 4.0);
+    }
+
+    #[test]
+    fn example() {
+        let robots = motion::parse(EXAMPLE).unwrap();
+        let reports = scan(&robots, &standard(), 0..77, 11, 7);
+        assert_eq!(
+            reports.iter().map(|report| report.name).collect::<Vec<_>>(),
+            ["neighbours", "entropy", "border"]
+        );
+        assert_eq!(reports[0].first, None);
+        assert!(reports[0].best <= 0.7);
+        let touching = Neighbours { fraction: 0.0 };
+        assert_eq!(first(&robots, &touching, 0..77, 11, 7), Some(0));
+        assert_eq!(first(&robots, &Neighbours::default(), 0..77, 11, 7), None);
+    }
+}
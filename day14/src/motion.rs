@@ -0,0 +1,257 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Missing position prefix")]
+    MissingPositionPrefix,
+    #[error("Missing velocity prefix")]
+    MissingVelocityPrefix,
+    #[error("Missing delimiter")]
+    MissingDelimiter,
+    #[error("Invalid input number: {0}")]
+    InvalidInputNumber(#[from] std::num::ParseIntError),
+    #[error("No robots")]
+    NoRobots,
+    #[error("No time matches offset {0} mod {1} and offset {2} mod {3}")]
+    NoCommonTime(usize, usize, usize, usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Robot {
+    pub x: i32,
+    pub y: i32,
+    pub dx: i32,
+    pub dy: i32,
+}
+
+impl Robot {
+    pub fn parse(line: &str) -> Result<Self, Error> {
+        let (pos, vec) = line
+            .strip_prefix("p=")
+            .ok_or(Error::MissingPositionPrefix)?
+            .split_once(" v=")
+            .ok_or(Error::MissingVelocityPrefix)?;
+        let (x, y) = pos.split_once(',').ok_or(Error::MissingDelimiter)?;
+        let (dx, dy) = vec.split_once(',').ok_or(Error::MissingDelimiter)?;
+        Ok(Robot {
+            x: x.parse()?,
+            y: y.parse()?,
+            dx: dx.parse()?,
+            dy: dy.parse()?,
+        })
+    }
+
+    /// Where the robot is after `seconds`, wrapping around the edges.
+    pub fn position(&self, seconds: usize, width: i32, height: i32) -> (i32, i32) {
+        (
+            wrap(self.x, self.dx, seconds, width),
+            wrap(self.y, self.dy, seconds, height),
+        )
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Robot>, Error> {
+    input.lines().map(Robot::parse).collect()
+}
+
+/// The best offset along one axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Axis {
+    /// Seconds until every robot is back where it started along this axis.
+    pub period: usize,
+    /// Seconds into the period at which the positions vary the least.
+    pub offset: usize,
+    pub variance: f64,
+    /// Standard deviations between the variance at `offset` and the mean
+    /// variance over the period.
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Analysis {
+    pub x: Axis,
+    pub y: Axis,
+    /// The first second at which both axes are at their best offset.
+    pub time: usize,
+    /// Seconds until every robot is back where it started.
+    pub period: usize,
+    /// Width times height, which is the period when both are prime and no
+    /// robot stands still along an axis.
+    pub expected_period: usize,
+    /// The lower of the two axis scores. Scores above about 5 are unlikely to
+    /// happen by chance.
+    pub confidence: f64,
+}
+
+impl Analysis {
+    pub fn has_expected_period(&self) -> bool {
+        self.period == self.expected_period
+    }
+}
+
+/// Finds when the robots bunch up along each axis separately, which only takes
+/// one period per axis, and combines the two offsets with the Chinese
+/// remainder theorem.
+pub fn analyse(robots: &[Robot], width: i32, height: i32) -> Result<Analysis, Error> {
+    if robots.is_empty() {
+        return Err(Error::NoRobots);
+    }
+    let x = axis(robots.iter().map(|robot| (robot.x, robot.dx)), width);
+    let y = axis(robots.iter().map(|robot| (robot.y, robot.dy)), height);
+    let (time, period) = crt(x.offset, x.period, y.offset, y.period)
+        .ok_or(Error::NoCommonTime(x.offset, x.period, y.offset, y.period))?;
+    Ok(Analysis {
+        x,
+        y,
+        time,
+        period,
+        expected_period: width as usize * height as usize,
+        confidence: x.score.min(y.score),
+    })
+}
+
+fn axis(robots: impl Iterator<Item = (i32, i32)> + Clone, size: i32) -> Axis {
+    let period = robots.clone().fold(1, |period, (_, velocity)| {
+        let step = velocity.rem_euclid(size) as usize;
+        lcm(period, size as usize / gcd(step, size as usize))
+    });
+    let variances = (0..period)
+        .map(|seconds| {
+            let positions = robots
+                .clone()
+                .map(|(position, velocity)| wrap(position, velocity, seconds, size) as f64);
+            variance(positions)
+        })
+        .collect::<Vec<_>>();
+    let (offset, &best) = variances
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .expect("period is at least 1");
+    let mean = variances.iter().sum::<f64>() / period as f64;
+    let deviation = variance(variances.iter().copied()).sqrt();
+    let score = if deviation > 0.0 {
+        (mean - best) / deviation
+    } else {
+        0.0
+    };
+    Axis {
+        period,
+        offset,
+        variance: best,
+        score,
+    }
+}
+
+fn wrap(position: i32, velocity: i32, seconds: usize, size: i32) -> i32 {
+    let moved = velocity as i64 * (seconds as i64 % size as i64);
+    (position as i64 + moved).rem_euclid(size as i64) as i32
+}
+
+fn variance(values: impl Iterator<Item = f64>) -> f64 {
+    let (count, sum, squares) = values.fold((0.0, 0.0, 0.0), |(count, sum, squares), value| {
+        (count + 1.0, sum + value, squares + value * value)
+    });
+    squares / count - (sum / count) * (sum / count)
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+/// The smallest `t` with `t = a mod m` and `t = b mod n`, and the period after
+/// which it repeats. The moduli need not be coprime.
+fn crt(a: usize, m: usize, b: usize, n: usize) -> Option<(usize, usize)> {
+    let g = gcd(m, n);
+    let (a, b) = (a as i64, b as i64);
+    if (b - a) % g as i64 != 0 {
+        return None;
+    }
+    let (m, n, g) = (m as i64, n as i64, g as i64);
+    let modulus = n / g;
+    let inverse = (0..modulus).find(|k| (m / g * k).rem_euclid(modulus) == 1 % modulus)?;
+    let k = ((b - a) / g * inverse).rem_euclid(modulus);
+    let period = m * modulus;
+    Some(((a + m * k).rem_euclid(period) as usize, period as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    /// Robots with pseudo-random velocities, bunched together at `time` if given.
+    fn robots(count: usize, width: i32, height: i32, time: Option<usize>) -> Vec<Robot> {
+        let mut seed = 0x2024_u64;
+        let mut random = move |below: i32| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % below as u64) as i32
+        };
+        (0..count)
+            .map(|_| {
+                let (dx, dy) = (random(201) - 100, random(201) - 100);
+                let (x, y) = match time {
+                    Some(_) => (40 + random(20), 50 + random(20)),
+                    None => (random(width), random(height)),
+                };
+                let seconds = time.unwrap_or(0);
+                let (x, y) = Robot {
+                    x,
+                    y,
+                    dx: -dx,
+                    dy: -dy,
+                }
+                .position(seconds, width, height);
+                Robot { x, y, dx, dy }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn picture() {
+        let robots = robots(500, 101, 103, Some(6789));
+        let analysis = analyse(&robots, 101, 103).unwrap();
+        assert_eq!(analysis.time, 6789);
+        assert_eq!(
+            (analysis.x.offset, analysis.y.offset),
+            (6789 % 101, 6789 % 103)
+        );
+        assert!(analysis.has_expected_period());
+        assert!(analysis.confidence > 5.0, "{analysis:?}");
+
+        let noise = analyse(&self::robots(500, 101, 103, None), 101, 103).unwrap();
+        assert!(noise.confidence < 5.0, "{noise:?}");
+    }
+
+    #[test]
+    fn periods() {
+        let robots = parse(EXAMPLE).unwrap();
+        let analysis = analyse(&robots, 11, 7).unwrap();
+        assert_eq!(analysis.period, 77);
+        assert!(analysis.has_expected_period());
+
+        // Every horizontal velocity is even, so x repeats after 5 seconds, and
+        // 10 and 4 share a factor, so the whole pattern repeats after 20.
+        let robots = parse("p=1,1 v=2,1\np=3,0 v=-4,3").unwrap();
+        let analysis = analyse(&robots, 10, 4).unwrap();
+        assert_eq!((analysis.x.period, analysis.y.period), (5, 4));
+        assert_eq!(analysis.period, 20);
+        assert!(!analysis.has_expected_period());
+
+        assert_eq!(crt(2, 4, 3, 6), None);
+        assert_eq!(crt(2, 4, 4, 6), Some((10, 12)));
+        assert_eq!(crt(0, 1, 0, 1), Some((0, 1)));
+        assert_eq!(analyse(&[], 11, 7), Err(Error::NoRobots));
+    }
+}
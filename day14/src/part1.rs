@@ -12,14 +12,36 @@ pub enum Error {
     InvalidInputNumber(#[from] std::num::ParseIntError),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    pub width: i32,
+    pub height: i32,
+    pub seconds: i32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            width: 101,
+            height: 103,
+            seconds: 100,
+        }
+    }
+}
+
 pub fn solve(input: &str) -> Result<i32, Error> {
-    safety_factor(input, 101, 103)
+    solve_with(input, Params::default())
 }
 
-fn safety_factor(input: &str, width: i32, height: i32) -> Result<i32, Error> {
+pub fn solve_with(input: &str, params: Params) -> Result<i32, Error> {
+    let Params {
+        width,
+        height,
+        seconds,
+    } = params;
     Ok(input
         .lines()
-        .map(|line| Ok::<_, Error>(Robot::parse(line)?.simulate(width, height, 100)))
+        .map(|line| Ok::<_, Error>(Robot::parse(line)?.simulate(width, height, seconds)))
         .try_fold([0; 4], |mut acc, robot| {
             let quadrant = robot?.quadrant(width, height);
             if quadrant < 4 {
@@ -100,7 +122,12 @@ mod tests {
 
     #[test]
     fn example() {
-        let result = safety_factor(EXAMPLE, 11, 7).unwrap();
+        let params = Params {
+            width: 11,
+            height: 7,
+            seconds: 100,
+        };
+        let result = solve_with(EXAMPLE, params).unwrap();
         assert_eq!(result, 12);
     }
 
@@ -1,135 +1,89 @@
-use rayon::prelude::*;
+use crate::{
+    detect::{self, Neighbours},
+    motion::{self, Robot},
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Missing position prefix")]
-    MissingPositionPrefix,
-    #[error("Missing velocity prefix")]
-    MissingVelocityPrefix,
-    #[error("Missing delimiter")]
-    MissingDelimiter,
-    #[error("Invalid input number: {0}")]
-    InvalidInputNumber(#[from] std::num::ParseIntError),
+    #[error("Invalid robots: {0}")]
+    InvalidRobots(#[from] motion::Error),
+    #[error("No Easter egg found")]
+    NoEasterEgg,
 }
 
-pub fn solve(input: &str) -> Result<usize, Error> {
-    find_easter_egg(input, 101, 103)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Step second by second until most robots stand next to another.
+    Neighbours,
+    /// Find the least spread out offset along each axis and combine them.
+    Variance,
 }
 
-fn find_easter_egg(input: &str, width: i32, height: i32) -> Result<usize, Error> {
-    let mut robots = input
-        .lines()
-        .map(|line| Robot::parse(line, width, height))
-        .collect::<Result<Vec<_>, Error>>()?;
-
-    let limit = robots.len() * 70 / 100;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    pub width: i32,
+    pub height: i32,
+    pub method: Method,
+}
 
-    for seconds in 0.. {
-        let neighbours = count_neighbours(&robots);
-        if neighbours > limit {
-            #[cfg(debug_assertions)]
-            print_grid(&robots, width, height);
-            #[cfg(debug_assertions)]
-            println!("neighbours: {} > limit: {}", neighbours, limit);
-            return Ok(seconds);
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            width: 101,
+            height: 103,
+            method: Method::Neighbours,
         }
-        robots
-            .iter_mut()
-            .for_each(|robot| *robot = robot.next(width, height));
     }
-
-    unreachable!("no solution found")
 }
 
-fn count_neighbours(robots: &[Robot]) -> usize {
-    robots
-        .par_iter()
-        .enumerate()
-        .map(|(i, robot)| robot.count_neighbours(&robots[i + 1..]))
-        .sum()
+pub fn solve(input: &str) -> Result<usize, Error> {
+    solve_with(input, Params::default())
 }
 
-#[allow(dead_code)]
-fn print_grid(robots: &[Robot], width: i32, height: i32) {
-    for y in 0..height {
-        for x in 0..width {
-            let count = robots
-                .iter()
-                .filter(|robot| robot.x == x && robot.y == y)
-                .count();
-            if count > 0 {
-                print!("{}", count);
-            } else {
-                print!(".");
-            }
-        }
-        println!();
+pub fn solve_with(input: &str, params: Params) -> Result<usize, Error> {
+    let Params {
+        width,
+        height,
+        method,
+    } = params;
+    let robots = motion::parse(input)?;
+    match method {
+        Method::Neighbours => find_neighbours(&robots, width, height),
+        Method::Variance => Ok(motion::analyse(&robots, width, height)?.time),
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Robot {
-    x: i32,
-    y: i32,
-    dx: i32,
-    dy: i32,
+fn find_neighbours(robots: &[Robot], width: i32, height: i32) -> Result<usize, Error> {
+    // The robots are back where they started after at most width * height seconds.
+    let seconds = 0..width as usize * height as usize;
+    detect::first(robots, &Neighbours::default(), seconds, width, height).ok_or(Error::NoEasterEgg)
 }
 
-impl Robot {
-    fn parse(line: &str, width: i32, height: i32) -> Result<Self, Error> {
-        let (pos, vec) = line
-            .strip_prefix("p=")
-            .ok_or(Error::MissingPositionPrefix)?
-            .split_once(" v=")
-            .ok_or(Error::MissingVelocityPrefix)?;
-        let (x, y) = pos.split_once(',').ok_or(Error::MissingDelimiter)?;
-        let (dx, dy) = vec.split_once(',').ok_or(Error::MissingDelimiter)?;
-        let mut dx = dx.parse()?;
-        let mut dy = dy.parse()?;
-        if dx < 0 {
-            dx += width
-        }
-        if dy < 0 {
-            dy += height
-        }
-        Ok(Robot {
-            x: x.parse()?,
-            y: y.parse()?,
-            dx,
-            dy,
-        })
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn next(self, width: i32, height: i32) -> Self {
-        let x = (self.x + self.dx) % width;
-        let y = (self.y + self.dy) % height;
-        Robot {
-            x,
-            y,
-            dx: self.dx,
-            dy: self.dy,
-        }
-    }
+    const EXAMPLE: &str = include_str!("../example.txt");
 
-    fn is_neighbour(&self, other: &Self) -> bool {
-        matches!(
-            (self.x.abs_diff(other.x), (self.y.abs_diff(other.y))),
-            (1, 0) | (0, 1)
-        )
-    }
+    #[test]
+    fn example() {
+        // The example robots never draw a picture.
+        let params = Params {
+            width: 11,
+            height: 7,
+            method: Method::Neighbours,
+        };
+        let result = solve_with(EXAMPLE, params);
+        assert!(matches!(result, Err(Error::NoEasterEgg)));
 
-    fn count_neighbours(&self, robots: &[Self]) -> usize {
-        robots
-            .iter()
-            .filter(|robot| self.is_neighbour(robot))
-            .count()
+        let params = Params {
+            method: Method::Variance,
+            ..params
+        };
+        let result = solve_with(EXAMPLE, params).unwrap();
+        assert!(result < 77);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[cfg(input_txt)]
     #[cfg(part2_txt)]
@@ -139,4 +93,17 @@ mod tests {
         let result = solve(super::super::INPUT).unwrap();
         assert_eq!(result, expected);
     }
+
+    #[cfg(input_txt)]
+    #[cfg(part2_txt)]
+    #[test]
+    fn result_variance() {
+        let expected = include_str!("../part2.txt").trim().parse().unwrap();
+        let params = Params {
+            method: Method::Variance,
+            ..Default::default()
+        };
+        let result = solve_with(super::super::INPUT, params).unwrap();
+        assert_eq!(result, expected);
+    }
 }
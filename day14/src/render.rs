@@ -0,0 +1,234 @@
+use crate::motion::Robot;
+use aoc_grid::{Grid, Point};
+use std::io::{self, Write};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("No frames to draw")]
+    NoFrames,
+    #[error("Frames differ in size")]
+    MismatchedFrames,
+    #[error("Image too large: {0}x{1}")]
+    TooLarge(usize, usize),
+    #[error("Write failed: {0}")]
+    Io(#[from] io::Error),
+    #[error("PNG encoding failed: {0}")]
+    Png(#[from] png::EncodingError),
+}
+
+/// Grey levels for empty tiles, tiles with robots and the lines between frames
+/// of a sheet.
+const EMPTY: u8 = 0x00;
+const ROBOT: u8 = 0xff;
+const GAP: u8 = 0x80;
+
+/// Bits per line of a plain PBM, which should stay under 70 characters.
+const PBM_LINE: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// Pixels per tile along each side.
+    pub scale: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self { scale: 4 }
+    }
+}
+
+/// The number of robots on each tile after `seconds`, saturating at 255.
+pub fn frame(robots: &[Robot], seconds: usize, width: i32, height: i32) -> Grid<u8> {
+    let mut frame = Grid::new(width as usize, height as usize, 0u8);
+    for robot in robots {
+        let (x, y) = robot.position(seconds, width, height);
+        let count = &mut frame[Point::new(y, x)];
+        *count = count.saturating_add(1);
+    }
+    frame
+}
+
+/// The frame the way the puzzle draws it, with `+` for more than nine robots.
+pub fn text(frame: &Grid<u8>) -> String {
+    let mut text = String::with_capacity((frame.width() + 1) * frame.height());
+    for row in frame.rows() {
+        text.extend(row.iter().map(|&count| match count {
+            0 => '.',
+            1..=9 => (b'0' + count) as char,
+            _ => '+',
+        }));
+        text.push('\n');
+    }
+    text
+}
+
+/// A frame as grey levels: white where there are robots and black elsewhere.
+pub fn image(frame: &Grid<u8>) -> Grid<u8> {
+    frame.map(|&count| shade(count))
+}
+
+/// The frames in rows of `columns`, separated by grey lines one tile wide.
+pub fn sheet(frames: &[Grid<u8>], columns: usize) -> Result<Grid<u8>, Error> {
+    let first = frames.first().ok_or(Error::NoFrames)?;
+    if frames
+        .iter()
+        .any(|frame| (frame.width(), frame.height()) != (first.width(), first.height()))
+    {
+        return Err(Error::MismatchedFrames);
+    }
+    let columns = columns.clamp(1, frames.len());
+    let rows = frames.len().div_ceil(columns);
+    let (width, height) = (first.width() + 1, first.height() + 1);
+    let mut sheet = Grid::new(columns * width - 1, rows * height - 1, GAP);
+    for (index, frame) in frames.iter().enumerate() {
+        let origin = Point::new(
+            (index / columns * height) as i32,
+            (index % columns * width) as i32,
+        );
+        for (position, &count) in frame.iter() {
+            sheet[origin + position] = shade(count);
+        }
+    }
+    Ok(sheet)
+}
+
+/// Writes grey levels as a greyscale PNG.
+pub fn write_png(writer: impl Write, image: &Grid<u8>, options: Options) -> Result<(), Error> {
+    let (width, height) = size(image, options)?;
+    let mut encoder = png::Encoder::new(writer, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels(image, options))?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Writes grey levels as a plain PBM. Robots and the lines between frames are
+/// inked, so they come out black on white.
+pub fn write_pbm(mut writer: impl Write, image: &Grid<u8>, options: Options) -> Result<(), Error> {
+    let (width, height) = size(image, options)?;
+    writeln!(writer, "P1\n{width} {height}")?;
+    for row in pixels(image, options).chunks(width) {
+        for bits in row.chunks(PBM_LINE) {
+            let line = bits
+                .iter()
+                .map(|&grey| if grey >= GAP { '1' } else { '0' })
+                .collect::<String>();
+            writeln!(writer, "{line}")?;
+        }
+    }
+    Ok(())
+}
+
+fn shade(count: u8) -> u8 {
+    if count > 0 {
+        ROBOT
+    } else {
+        EMPTY
+    }
+}
+
+fn size(image: &Grid<u8>, options: Options) -> Result<(usize, usize), Error> {
+    let (width, height) = (
+        image.width() * options.scale,
+        image.height() * options.scale,
+    );
+    if width == 0 || height == 0 || width > u16::MAX as usize || height > u16::MAX as usize {
+        return Err(Error::TooLarge(width, height));
+    }
+    Ok((width, height))
+}
+
+/// Grey levels scaled up, one byte per pixel.
+fn pixels(image: &Grid<u8>, options: Options) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(image.len() * options.scale * options.scale);
+    for row in image.rows() {
+        let line = row
+            .iter()
+            .flat_map(|&grey| std::iter::repeat_n(grey, options.scale))
+            .collect::<Vec<_>>();
+        for _ in 0..options.scale {
+            pixels.extend_from_slice(&line);
+        }
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motion;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn frames() {
+        let robots = motion::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            text(&frame(&robots, 0, 11, 7)),
+            "1.12.......\n\
+             ...........\n\
+             ...........\n\
+             ......11.11\n\
+             1.1........\n\
+             .........1.\n\
+             .......1...\n"
+        );
+        assert_eq!(
+            text(&frame(&robots, 100, 11, 7)),
+            "......2..1.\n\
+             ...........\n\
+             1..........\n\
+             .11........\n\
+             .....1.....\n\
+             ...12......\n\
+             .1....1....\n"
+        );
+        let crowd = vec![robots[0]; 300];
+        assert_eq!(text(&frame(&crowd, 0, 3, 1)), "+..\n");
+        assert_eq!(frame(&crowd, 0, 3, 1).cells(), [255, 0, 0]);
+    }
+
+    #[test]
+    fn images() {
+        let robots = motion::parse(EXAMPLE).unwrap();
+        let frames = (0..5)
+            .map(|seconds| frame(&robots, seconds, 11, 7))
+            .collect::<Vec<_>>();
+        let sheet = sheet(&frames, 2).unwrap();
+        assert_eq!((sheet.width(), sheet.height()), (23, 23));
+        assert_eq!(sheet[Point::new(0, 0)], ROBOT);
+        assert_eq!(sheet[Point::new(0, 11)], GAP);
+        assert_eq!(sheet[Point::new(22, 22)], GAP);
+        assert_eq!(
+            image(&frames[1]),
+            Grid::from_fn(11, 7, |position| sheet[position + Point::new(0, 12)])
+        );
+
+        let options = Options { scale: 2 };
+        let mut png = vec![];
+        write_png(&mut png, &sheet, options).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+
+        let mut pbm = vec![];
+        write_pbm(&mut pbm, &image(&frames[0]), options).unwrap();
+        let pbm = String::from_utf8(pbm).unwrap();
+        let mut lines = pbm.lines();
+        assert_eq!(lines.next(), Some("P1"));
+        assert_eq!(lines.next(), Some("22 14"));
+        assert_eq!(lines.next(), Some("1100111100000000000000"));
+        assert_eq!(lines.count(), 13);
+
+        assert!(matches!(super::sheet(&[], 2), Err(Error::NoFrames)));
+        assert!(matches!(
+            super::sheet(&[frames[0].clone(), Grid::new(3, 3, 0)], 2),
+            Err(Error::MismatchedFrames)
+        ));
+        assert!(matches!(
+            write_png(vec![], &sheet, Options { scale: 0 }),
+            Err(Error::TooLarge(0, 0))
+        ));
+    }
+}
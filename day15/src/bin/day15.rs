@@ -0,0 +1,59 @@
+use day15::{
+    render::{self, Options},
+    warehouse,
+};
+use std::{error::Error, fs, io::BufWriter};
+
+const USAGE: &str = "\
+Usage: day15 text <file> [--wide]
+       day15 gif <file> <output> [--wide] [--every <moves>]
+       day15 apng <file> <output> [--wide] [--every <moves>]";
+
+/// Animations are cut down to about this many frames unless `--every` is given.
+const MAX_FRAMES: usize = 500;
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(2);
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let wide = args.iter().any(|arg| arg == "--wide");
+    args.retain(|arg| arg != "--wide");
+    let every = match args.iter().position(|arg| arg == "--every") {
+        Some(index) if index + 1 < args.len() => {
+            let every = args.remove(index + 1).parse::<usize>()?;
+            args.remove(index);
+            Some(every)
+        }
+        Some(_) => usage(),
+        None => None,
+    };
+
+    let (command, path, output) = match args.as_slice() {
+        [command, path] => (command.as_str(), path, None),
+        [command, path, output] => (command.as_str(), path, Some(output)),
+        _ => usage(),
+    };
+    let (mut warehouse, moves) = warehouse::parse(&fs::read_to_string(path)?)?;
+    if wide {
+        warehouse = warehouse.widen()?;
+    }
+    let every = every.unwrap_or(moves.len().div_ceil(MAX_FRAMES));
+    match (command, output) {
+        ("text", None) => print!("{}", render::text(&mut warehouse, &moves)?),
+        ("gif", Some(output)) => {
+            let frames = render::record(&mut warehouse, &moves, every)?;
+            let file = BufWriter::new(fs::File::create(output)?);
+            render::write_gif(file, &frames, Options::default())?;
+        }
+        ("apng", Some(output)) => {
+            let frames = render::record(&mut warehouse, &moves, every)?;
+            let file = BufWriter::new(fs::File::create(output)?);
+            render::write_apng(file, &frames, Options::default())?;
+        }
+        _ => usage(),
+    }
+    Ok(())
+}
@@ -1,7 +1,16 @@
+use aoc_common::{Solution, Solver};
+
 pub mod part1;
 pub mod part2;
+pub mod render;
+pub mod warehouse;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
 #[cfg(not(input_txt))]
 pub const INPUT: &str = include_str!("../example.txt");
+
+pub static SOLUTIONS: [&dyn Solution; 2] = [
+    &Solver::new(15, 1, part1::solve),
+    &Solver::new(15, 2, part2::solve),
+];
@@ -0,0 +1,187 @@
+use crate::warehouse::{self, Warehouse};
+use aoc_grid::{Direction, Grid};
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Warehouse error: {0}")]
+    Warehouse(#[from] warehouse::Error),
+    #[error("No frames to write")]
+    NoFrames,
+    #[error("Frames differ in size")]
+    MismatchedFrames,
+    #[error("Image too large: {0}x{1}")]
+    TooLarge(usize, usize),
+    #[error("GIF encoding failed: {0}")]
+    Gif(#[from] gif::EncodingError),
+    #[error("PNG encoding failed: {0}")]
+    Png(#[from] png::EncodingError),
+}
+
+/// Floor, wall, crate and robot colours, in palette order.
+const PALETTE: [u8; 12] = [
+    0x10, 0x10, 0x18, // floor
+    0x70, 0x70, 0x78, // wall
+    0xc0, 0x8a, 0x40, // box
+    0xe0, 0x30, 0x30, // robot
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// Pixels per tile along each side.
+    pub scale: usize,
+    /// Time each frame is shown, in hundredths of a second.
+    pub delay: u16,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self { scale: 4, delay: 5 }
+    }
+}
+
+/// The map before the first move and after every `every` moves, ending with the
+/// final map.
+pub fn record(
+    warehouse: &mut Warehouse,
+    moves: &[Direction],
+    every: usize,
+) -> Result<Vec<Grid<u8>>, Error> {
+    let mut frames = vec![warehouse.map().clone()];
+    for (index, &direction) in moves.iter().enumerate() {
+        warehouse.step(direction)?;
+        if (index + 1) % every.max(1) == 0 || index + 1 == moves.len() {
+            frames.push(warehouse.map().clone());
+        }
+    }
+    Ok(frames)
+}
+
+/// Every frame as text, separated by the move that led to it.
+pub fn text(warehouse: &mut Warehouse, moves: &[Direction]) -> Result<String, Error> {
+    let mut text = format!("Initial state:\n{warehouse}");
+    for &direction in moves {
+        warehouse.step(direction)?;
+        text += &format!("\nMove {}:\n{warehouse}", direction.arrow() as char);
+    }
+    Ok(text)
+}
+
+/// Writes the frames as a looping animated GIF.
+pub fn write_gif(writer: impl Write, frames: &[Grid<u8>], options: Options) -> Result<(), Error> {
+    let (width, height) = size(frames, options)?;
+    let (width, height) = (width as u16, height as u16);
+    let mut encoder = gif::Encoder::new(writer, width, height, &PALETTE)?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    for map in frames {
+        let mut frame = gif::Frame::from_indexed_pixels(width, height, pixels(map, options), None);
+        frame.delay = options.delay;
+        encoder.write_frame(&frame)?;
+    }
+    Ok(())
+}
+
+/// Writes the frames as a looping animated PNG.
+pub fn write_apng(writer: impl Write, frames: &[Grid<u8>], options: Options) -> Result<(), Error> {
+    let (width, height) = size(frames, options)?;
+    let mut encoder = png::Encoder::new(writer, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_palette(&PALETTE[..]);
+    encoder.set_animated(frames.len() as u32, 0)?;
+    encoder.set_frame_delay(options.delay, 100)?;
+    let mut writer = encoder.write_header()?;
+    for map in frames {
+        writer.write_image_data(&pixels(map, options))?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// The image size shared by all frames.
+fn size(frames: &[Grid<u8>], options: Options) -> Result<(usize, usize), Error> {
+    let first = frames.first().ok_or(Error::NoFrames)?;
+    if frames
+        .iter()
+        .any(|frame| (frame.width(), frame.height()) != (first.width(), first.height()))
+    {
+        return Err(Error::MismatchedFrames);
+    }
+    let (width, height) = (
+        first.width() * options.scale,
+        first.height() * options.scale,
+    );
+    if width == 0 || height == 0 || width > u16::MAX as usize || height > u16::MAX as usize {
+        return Err(Error::TooLarge(width, height));
+    }
+    Ok((width, height))
+}
+
+/// Palette indices for the map, one byte per pixel.
+fn pixels(map: &Grid<u8>, options: Options) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(map.len() * options.scale * options.scale);
+    for row in map.rows() {
+        let line = row
+            .iter()
+            .flat_map(|tile| {
+                let colour = match tile {
+                    b'.' => 0,
+                    b'#' => 1,
+                    b'@' => 3,
+                    _ => 2,
+                };
+                std::iter::repeat_n(colour, options.scale)
+            })
+            .collect::<Vec<_>>();
+        for _ in 0..options.scale {
+            pixels.extend_from_slice(&line);
+        }
+    }
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::warehouse::parse;
+
+    const EXAMPLE2: &str = include_str!("../example2.txt");
+
+    #[test]
+    fn frames() {
+        let (mut warehouse, moves) = parse(EXAMPLE2).unwrap();
+        let mut recorded = warehouse.clone();
+        let frames = record(&mut recorded, &moves, 5).unwrap();
+        assert_eq!(frames.len(), 1 + moves.len().div_ceil(5));
+        assert_eq!(frames[0], *warehouse.map());
+        assert_eq!(frames.last(), Some(recorded.map()));
+
+        let text = text(&mut warehouse, &moves).unwrap();
+        assert!(text.starts_with("Initial state:\n########\n#..O.O.#\n##@.O..#\n"));
+        assert!(text.contains("\nMove <:\n########\n#..O.O.#\n##@.O..#\n"));
+        assert!(text.ends_with(&format!("{warehouse}")));
+    }
+
+    #[test]
+    fn images() {
+        let (mut warehouse, moves) = parse(EXAMPLE2).unwrap();
+        let frames = record(&mut warehouse, &moves, 1).unwrap();
+        let options = Options { scale: 2, delay: 1 };
+
+        let mut gif = vec![];
+        write_gif(&mut gif, &frames, options).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+        assert_eq!(&gif[6..10], [16, 0, 16, 0]);
+
+        let mut apng = vec![];
+        write_apng(&mut apng, &frames, options).unwrap();
+        assert!(apng.starts_with(b"\x89PNG"));
+        assert!(apng.windows(4).any(|chunk| chunk == b"acTL"));
+
+        assert!(matches!(
+            write_gif(vec![], &[], options),
+            Err(Error::NoFrames)
+        ));
+    }
+}
@@ -0,0 +1,470 @@
+use aoc_grid::{Direction, Grid, Point};
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Missing delimiter")]
+    MissingDelimiter,
+    #[error("Invalid map: {0}")]
+    InvalidMap(#[from] aoc_grid::Error),
+    #[error("Robot not found")]
+    RobotNotFound,
+    #[error("Invalid move: {0}")]
+    InvalidMove(char),
+    #[error("Invalid tile: {0}")]
+    InvalidTile(char),
+    #[error("Invalid crate shape: {0}")]
+    InvalidShape(String),
+    #[error("Out of bounds")]
+    OutOfBounds,
+}
+
+/// Splits the puzzle input into the warehouse and the robots' moves.
+///
+/// The map may be preceded by a header of `crate <shape>` lines and a blank line,
+/// declaring crate shapes on top of `O` and `[]`.
+pub fn parse(input: &str) -> Result<(Warehouse, Vec<Direction>), Error> {
+    let (shapes, input) = match input.strip_prefix("crate ") {
+        Some(_) => input.split_once("\n\n").ok_or(Error::MissingDelimiter)?,
+        None => ("", input),
+    };
+    let shapes = shapes
+        .lines()
+        .map(|line| {
+            let pattern = line
+                .strip_prefix("crate ")
+                .ok_or_else(|| Error::InvalidShape(line.to_owned()))?;
+            pattern.parse()
+        })
+        .collect::<Result<Vec<Shape>, _>>()?;
+    let (map, moves) = input.split_once("\n\n").ok_or(Error::MissingDelimiter)?;
+    let warehouse = Warehouse::with_shapes(Grid::parse(map)?, &shapes)?;
+    let moves = moves
+        .lines()
+        .flat_map(|line| line.as_bytes().iter().copied())
+        .map(|next| Direction::from_arrow(next).ok_or(Error::InvalidMove(next as char)))
+        .collect::<Result<_, _>>()?;
+    Ok((warehouse, moves))
+}
+
+/// A connected crate, written as rows of tiles separated by `/` with `.` for gaps,
+/// such as `[]` or `L./LL`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shape {
+    /// Each tile with its offset from the first tile in reading order.
+    tiles: Vec<(Point, u8)>,
+}
+
+impl Shape {
+    pub fn tiles(&self) -> &[(Point, u8)] {
+        &self.tiles
+    }
+}
+
+impl std::str::FromStr for Shape {
+    type Err = Error;
+
+    fn from_str(pattern: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidShape(pattern.to_owned());
+        let mut tiles = vec![];
+        for (row, line) in pattern.split('/').enumerate() {
+            for (column, tile) in line.bytes().enumerate() {
+                match tile {
+                    b'.' => (),
+                    b'#' | b'@' | b'/' => return Err(invalid()),
+                    _ if tile.is_ascii_graphic() => {
+                        tiles.push((Point::new(row as i32, column as i32), tile))
+                    }
+                    _ => return Err(invalid()),
+                }
+            }
+        }
+        let &(first, _) = tiles.first().ok_or_else(invalid)?;
+        for (point, _) in &mut tiles {
+            *point -= first;
+        }
+
+        let mut connected = vec![Point::ZERO];
+        let mut index = 0;
+        while let Some(&point) = connected.get(index) {
+            index += 1;
+            for next in point.neighbours() {
+                if !connected.contains(&next) && tiles.iter().any(|&(tile, _)| tile == next) {
+                    connected.push(next);
+                }
+            }
+        }
+        if connected.len() != tiles.len() {
+            return Err(invalid());
+        }
+        Ok(Self { tiles })
+    }
+}
+
+/// One move of a robot, with the tiles it pushed so that it can be undone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// The index of the robot that moved.
+    pub robot: usize,
+    pub direction: Direction,
+    /// Where the robot and every pushed crate tile were before the move. Empty if
+    /// the robot was blocked.
+    pub moved: Vec<Point>,
+}
+
+/// A warehouse map with crates of any shape and robots that take turns to move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warehouse {
+    map: Grid<u8>,
+    /// The index of the crate on each tile.
+    owners: Grid<Option<usize>>,
+    crates: Vec<Vec<Point>>,
+    robots: Vec<Point>,
+    history: Vec<Step>,
+}
+
+impl Warehouse {
+    /// A warehouse with `O` and `[]` crates only.
+    pub fn new(map: Grid<u8>) -> Result<Self, Error> {
+        Self::with_shapes(map, &[])
+    }
+
+    /// A warehouse with extra crate shapes. Tiles are matched to shapes in reading
+    /// order, trying `O` and `[]` first and then `shapes` in order.
+    pub fn with_shapes(map: Grid<u8>, shapes: &[Shape]) -> Result<Self, Error> {
+        let shapes = ["O".parse(), "[]".parse()]
+            .into_iter()
+            .collect::<Result<Vec<Shape>, _>>()?
+            .into_iter()
+            .chain(shapes.iter().cloned())
+            .collect::<Vec<_>>();
+        let mut owners = Grid::new(map.width(), map.height(), None);
+        let mut crates = vec![];
+        let mut robots = vec![];
+        for (point, &tile) in map.iter() {
+            match tile {
+                b'#' | b'.' => (),
+                b'@' => robots.push(point),
+                _ if owners[point].is_some() => (),
+                _ => {
+                    let cells = shapes
+                        .iter()
+                        .map(|shape| {
+                            shape
+                                .tiles
+                                .iter()
+                                .map(|&(offset, tile)| (point + offset, tile))
+                                .collect::<Vec<_>>()
+                        })
+                        .find(|cells| {
+                            cells.iter().all(|&(point, tile)| {
+                                map.get(point) == Some(&tile) && owners.get(point) == Some(&None)
+                            })
+                        })
+                        .ok_or(Error::InvalidTile(tile as char))?;
+                    for &(point, _) in &cells {
+                        owners[point] = Some(crates.len());
+                    }
+                    crates.push(cells.into_iter().map(|(point, _)| point).collect());
+                }
+            }
+        }
+        if robots.is_empty() {
+            return Err(Error::RobotNotFound);
+        }
+        Ok(Self {
+            map,
+            owners,
+            crates,
+            robots,
+            history: vec![],
+        })
+    }
+
+    pub fn parse(map: &str) -> Result<Self, Error> {
+        Self::new(Grid::parse(map)?)
+    }
+
+    /// The same warehouse with everything except the robots twice as wide. Only
+    /// `O` crates can be widened.
+    pub fn widen(&self) -> Result<Self, Error> {
+        let cells = self
+            .map
+            .cells()
+            .iter()
+            .map(|tile| match tile {
+                b'#' => Ok(*b"##"),
+                b'.' => Ok(*b".."),
+                b'O' => Ok(*b"[]"),
+                b'@' => Ok(*b"@."),
+                _ => Err(Error::InvalidTile(*tile as char)),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        Self::new(Grid::from_vec(
+            self.map.width() * 2,
+            self.map.height(),
+            cells,
+        )?)
+    }
+
+    pub fn map(&self) -> &Grid<u8> {
+        &self.map
+    }
+
+    /// The robots in turn order, which is reading order on the initial map.
+    pub fn robots(&self) -> &[Point] {
+        &self.robots
+    }
+
+    /// The tiles of every crate.
+    pub fn crates(&self) -> &[Vec<Point>] {
+        &self.crates
+    }
+
+    /// The moves made so far, oldest first.
+    pub fn history(&self) -> &[Step] {
+        &self.history
+    }
+
+    /// The index of the robot that makes the next move.
+    pub fn turn(&self) -> usize {
+        self.history.len() % self.robots.len()
+    }
+
+    /// Moves the next robot one tile, pushing any crates in the way. Returns whether
+    /// the robot moved.
+    pub fn step(&mut self, direction: Direction) -> Result<bool, Error> {
+        let robot = self.turn();
+        let moved = self.pushed(robot, direction)?.unwrap_or_default();
+        self.shift(robot, &moved, direction);
+        let result = !moved.is_empty();
+        self.history.push(Step {
+            robot,
+            direction,
+            moved,
+        });
+        Ok(result)
+    }
+
+    pub fn run(&mut self, moves: &[Direction]) -> Result<(), Error> {
+        for &direction in moves {
+            self.step(direction)?;
+        }
+        Ok(())
+    }
+
+    /// Reverts the most recent move, returning it, or `None` if there is none.
+    pub fn undo(&mut self) -> Option<Step> {
+        let step = self.history.pop()?;
+        let moved = step
+            .moved
+            .iter()
+            .map(|point| point.step(step.direction))
+            .collect::<Vec<_>>();
+        self.shift(step.robot, &moved, step.direction.reverse());
+        Some(step)
+    }
+
+    /// The sum of the GPS coordinates of every crate, measured to the top left
+    /// corner of the smallest rectangle around it.
+    pub fn gps_total(&self) -> usize {
+        self.crates
+            .iter()
+            .map(|tiles| {
+                let row = tiles.iter().map(|point| point.row).min().unwrap_or(0);
+                let column = tiles.iter().map(|point| point.column).min().unwrap_or(0);
+                100 * row as usize + column as usize
+            })
+            .sum()
+    }
+
+    /// The tiles the robot would push, starting with itself, or `None` if the chain
+    /// ends at a wall or another robot.
+    fn pushed(&self, robot: usize, direction: Direction) -> Result<Option<Vec<Point>>, Error> {
+        let mut moved = vec![self.robots[robot]];
+        let mut index = 0;
+        while let Some(&point) = moved.get(index) {
+            index += 1;
+            let next = point.step(direction);
+            match *self.map.get(next).ok_or(Error::OutOfBounds)? {
+                b'#' | b'@' => return Ok(None),
+                b'.' => (),
+                _ => {
+                    let owner =
+                        self.owners[next].ok_or(Error::InvalidTile(self.map[next] as char))?;
+                    for &tile in &self.crates[owner] {
+                        if !moved.contains(&tile) {
+                            moved.push(tile);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Some(moved))
+    }
+
+    /// Moves the tiles one step, along with the robot and crates on them.
+    fn shift(&mut self, robot: usize, points: &[Point], direction: Direction) {
+        if points.is_empty() {
+            return;
+        }
+        let tiles = points
+            .iter()
+            .map(|&point| (self.map[point], self.owners[point]))
+            .collect::<Vec<_>>();
+        for &point in points {
+            self.map[point] = b'.';
+            self.owners[point] = None;
+        }
+        for (&point, (tile, owner)) in points.iter().zip(tiles) {
+            let next = point.step(direction);
+            self.map[next] = tile;
+            self.owners[next] = owner;
+            if let Some(owner) = owner {
+                for tile in &mut self.crates[owner] {
+                    if *tile == point {
+                        *tile = next;
+                        break;
+                    }
+                }
+            }
+        }
+        self.robots[robot] = self.robots[robot].step(direction);
+    }
+}
+
+impl fmt::Display for Warehouse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn step_and_undo() {
+        let (mut warehouse, moves) = parse(EXAMPLE).unwrap();
+        let start = warehouse.clone();
+        warehouse.run(&moves).unwrap();
+        assert_eq!(warehouse.history().len(), moves.len());
+        assert_eq!(warehouse.gps_total(), 10092);
+        while warehouse.undo().is_some() {}
+        assert_eq!(warehouse, start);
+    }
+
+    #[test]
+    fn wide_boxes() {
+        let mut warehouse =
+            Warehouse::parse("#######\n#.....#\n#..O..#\n#..O@.#\n#.....#\n#######")
+                .unwrap()
+                .widen()
+                .unwrap();
+        assert!(warehouse.step(Direction::West).unwrap());
+        assert!(warehouse.step(Direction::South).unwrap());
+        assert!(warehouse.step(Direction::West).unwrap());
+        assert!(warehouse.step(Direction::North).unwrap());
+        assert_eq!(
+            warehouse.to_string(),
+            "##############\n\
+             ##....[]....##\n\
+             ##...[].....##\n\
+             ##....@.....##\n\
+             ##..........##\n\
+             ##############\n"
+        );
+        assert!(!warehouse.step(Direction::North).unwrap());
+        assert_eq!(warehouse.robots(), [Point::new(3, 6)]);
+        assert_eq!(warehouse.history()[3].moved.len(), 5);
+        assert!(warehouse.history()[4].moved.is_empty());
+
+        warehouse.undo();
+        warehouse.undo();
+        assert_eq!(
+            warehouse.to_string(),
+            "##############\n\
+             ##..........##\n\
+             ##....[]....##\n\
+             ##...[].....##\n\
+             ##....@.....##\n\
+             ##############\n"
+        );
+    }
+
+    #[test]
+    fn shapes() {
+        let input = "crate <=>\ncrate L./LL\n\n\
+                     #########\n\
+                     #.......#\n\
+                     #..L....#\n\
+                     #..LL...#\n\
+                     #.<=><=>#\n\
+                     #.....@.#\n\
+                     #########\n\
+                     \n^<<^";
+        let (mut warehouse, moves) = parse(input).unwrap();
+        assert_eq!(warehouse.crates().len(), 3);
+        assert_eq!(warehouse.gps_total(), 203 + 402 + 405);
+
+        warehouse.run(&moves).unwrap();
+        assert_eq!(
+            warehouse.to_string(),
+            "#########\n\
+             #..L....#\n\
+             #..LL...#\n\
+             #...@<=>#\n\
+             #<=>....#\n\
+             #.......#\n\
+             #########\n"
+        );
+        assert_eq!(warehouse.gps_total(), 103 + 401 + 305);
+    }
+
+    #[test]
+    fn robots_take_turns() {
+        let mut warehouse = Warehouse::parse("#######\n#@.O.@#\n#######").unwrap();
+        assert_eq!(warehouse.turn(), 0);
+        assert!(warehouse.step(Direction::East).unwrap());
+        assert_eq!(warehouse.turn(), 1);
+        assert!(warehouse.step(Direction::West).unwrap());
+        assert_eq!(warehouse.to_string(), "#######\n#.@O@.#\n#######\n");
+        assert!(!warehouse.step(Direction::East).unwrap());
+        assert!(!warehouse.step(Direction::West).unwrap());
+        assert_eq!(warehouse.robots(), [Point::new(1, 2), Point::new(1, 4)]);
+
+        warehouse.undo();
+        warehouse.undo();
+        assert!(warehouse.undo().is_some_and(|step| step.robot == 1));
+        assert_eq!(warehouse.to_string(), "#######\n#.@O.@#\n#######\n");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Warehouse::parse("###\n#x#\n###").unwrap_err(),
+            Error::InvalidTile('x')
+        );
+        assert_eq!(
+            Warehouse::parse("###\n#.#\n###").unwrap_err(),
+            Error::RobotNotFound
+        );
+        assert_eq!(
+            Warehouse::parse("#@[#\n#.]#").unwrap_err(),
+            Error::InvalidTile('[')
+        );
+        assert_eq!(parse("#@#\n\n<>x").unwrap_err(), Error::InvalidMove('x'));
+        assert_eq!(
+            "X./.X".parse::<Shape>().unwrap_err(),
+            Error::InvalidShape("X./.X".to_string())
+        );
+        assert!("A#".parse::<Shape>().is_err());
+        assert!("".parse::<Shape>().is_err());
+        let mut warehouse = Warehouse::parse("#.@").unwrap();
+        assert_eq!(warehouse.step(Direction::East), Err(Error::OutOfBounds));
+    }
+}
@@ -0,0 +1,38 @@
+use day16::maze::Maze;
+use std::{error::Error, fs};
+
+const USAGE: &str = "\
+Usage: day16 tiles <file>
+       day16 paths <file> [limit]";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(2);
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let (command, path, limit) = match args.as_slice() {
+        [command, path] => (command.as_str(), path, None),
+        [command, path, limit] => (command.as_str(), path, Some(limit.parse::<usize>()?)),
+        _ => usage(),
+    };
+    let maze = Maze::parse(&fs::read_to_string(path)?)?;
+    match (command, limit) {
+        ("tiles", None) => {
+            let tiles = maze.best_tiles()?;
+            print!("{}", maze.render_tiles(&tiles));
+            println!("{} tiles", tiles.len());
+        }
+        ("paths", limit) => {
+            for (index, path) in maze.best_paths()?.take(limit.unwrap_or(10)).enumerate() {
+                let actions = path.actions.iter().map(|action| action.to_string());
+                println!("Path {}, cost {}:", index + 1, path.cost);
+                println!("{}", actions.collect::<String>());
+                println!("{}", maze.render_path(&path));
+            }
+        }
+        _ => usage(),
+    }
+    Ok(())
+}
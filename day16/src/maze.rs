@@ -0,0 +1,553 @@
+#[cfg(feature = "grid-search")]
+use aoc_grid::search::{self, Distances};
+use aoc_grid::{Direction, Grid, Point};
+#[cfg(not(feature = "grid-search"))]
+use pathfinding::prelude::{astar_bag, dijkstra};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Invalid maze: {0}")]
+    InvalidMaze(#[from] aoc_grid::Error),
+    #[error("Start or end not found")]
+    StartOrEndNotFound,
+    #[error("No path to end found")]
+    NoPathToEndFound,
+    #[error("Cost of {0} must be at least 1")]
+    ZeroCost(String),
+}
+
+/// A reindeer's position and the way it is facing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Node {
+    pub position: Point,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Forward,
+    /// Step to the tile ahead and to the left, still facing the same way.
+    ForwardLeft,
+    /// Step to the tile ahead and to the right, still facing the same way.
+    ForwardRight,
+    TurnLeft,
+    TurnRight,
+    UTurn,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = match self {
+            Action::Forward => 'F',
+            Action::ForwardLeft => 'l',
+            Action::ForwardRight => 'r',
+            Action::TurnLeft => 'L',
+            Action::TurnRight => 'R',
+            Action::UTurn => 'U',
+        };
+        write!(f, "{letter}")
+    }
+}
+
+/// The cost of each action, and which actions are allowed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostModel {
+    pub forward: usize,
+    /// The cost of turning 90 degrees on the spot.
+    pub turn: usize,
+    /// The cost of turning around on the spot, if it can be done in one action.
+    pub u_turn: Option<usize>,
+    /// The cost of a diagonal step, if allowed. Diagonal steps may cut corners.
+    pub diagonal: Option<usize>,
+    /// Extra costs for stepping onto tiles, by map character.
+    pub terrain: HashMap<u8, usize>,
+}
+
+impl CostModel {
+    /// Checks that every cost is at least 1, as the searches need positive costs.
+    pub fn validate(&self) -> Result<(), Error> {
+        let costs = [
+            ("forward", Some(self.forward)),
+            ("turn", Some(self.turn)),
+            ("u-turn", self.u_turn),
+            ("diagonal", self.diagonal),
+        ];
+        if let Some((name, _)) = costs.iter().find(|(_, cost)| *cost == Some(0)) {
+            return Err(Error::ZeroCost(name.to_string()));
+        }
+        match self.terrain.iter().find(|(_, &cost)| cost == 0) {
+            Some((&tile, _)) => Err(Error::ZeroCost(format!("terrain {}", tile as char))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self {
+            forward: 1,
+            turn: 1000,
+            u_turn: None,
+            diagonal: None,
+            terrain: HashMap::new(),
+        }
+    }
+}
+
+/// A route from the start, with the action taken between each pair of nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub nodes: Vec<Node>,
+    pub actions: Vec<Action>,
+    pub cost: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maze {
+    grid: Grid<u8>,
+    start: Node,
+    end: Point,
+    costs: CostModel,
+}
+
+impl Maze {
+    /// Parses a maze with one `S`, where the reindeer starts facing east, and one `E`.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let grid = Grid::parse(input)?;
+        let start = Node {
+            position: grid.find(b'S').ok_or(Error::StartOrEndNotFound)?,
+            direction: Direction::East,
+        };
+        let end = grid.find(b'E').ok_or(Error::StartOrEndNotFound)?;
+        Ok(Self {
+            grid,
+            start,
+            end,
+            costs: CostModel::default(),
+        })
+    }
+
+    /// Uses `costs` in place of the puzzle's, which must all be at least 1.
+    pub fn with_costs(self, costs: CostModel) -> Result<Self, Error> {
+        costs.validate()?;
+        Ok(Self { costs, ..self })
+    }
+
+    pub fn costs(&self) -> &CostModel {
+        &self.costs
+    }
+
+    pub fn grid(&self) -> &Grid<u8> {
+        &self.grid
+    }
+
+    pub fn start(&self) -> Node {
+        self.start
+    }
+
+    pub fn end(&self) -> Point {
+        self.end
+    }
+
+    /// The nodes reachable from `node` with a single action, and what it costs.
+    pub fn successors(&self, node: Node) -> impl Iterator<Item = (Node, Action, usize)> + '_ {
+        let step = |position: Point, action, cost: Option<usize>| {
+            let tile = *self.grid.get(position)?;
+            let terrain = self.costs.terrain.get(&tile).copied().unwrap_or(0);
+            (tile != b'#').then_some((Node { position, ..node }, action, cost? + terrain))
+        };
+        let turn = |direction, action, cost: Option<usize>| {
+            cost.map(|cost| (Node { direction, ..node }, action, cost))
+        };
+        let ahead = node.position.step(node.direction);
+        let diagonal = self.costs.diagonal;
+        [
+            step(ahead, Action::Forward, Some(self.costs.forward)),
+            step(
+                ahead.step(node.direction.turn_left()),
+                Action::ForwardLeft,
+                diagonal,
+            ),
+            step(
+                ahead.step(node.direction.turn_right()),
+                Action::ForwardRight,
+                diagonal,
+            ),
+            turn(
+                node.direction.turn_left(),
+                Action::TurnLeft,
+                Some(self.costs.turn),
+            ),
+            turn(
+                node.direction.turn_right(),
+                Action::TurnRight,
+                Some(self.costs.turn),
+            ),
+            turn(node.direction.reverse(), Action::UTurn, self.costs.u_turn),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// The nodes from which `node` can be reached with a single action, and what it
+    /// costs.
+    pub fn predecessors(&self, node: Node) -> impl Iterator<Item = (Node, Action, usize)> + '_ {
+        let entered = match self.grid.get(node.position) {
+            Some(b'#') | None => None,
+            Some(tile) => Some(self.costs.terrain.get(tile).copied().unwrap_or(0)),
+        };
+        let step = move |position: Point, action, cost: Option<usize>| {
+            let tile = *self.grid.get(position)?;
+            (tile != b'#').then_some((Node { position, ..node }, action, cost? + entered?))
+        };
+        let turn = |direction, action, cost: Option<usize>| {
+            cost.map(|cost| (Node { direction, ..node }, action, cost))
+        };
+        let behind = node.position.step(node.direction.reverse());
+        let diagonal = self.costs.diagonal;
+        [
+            step(behind, Action::Forward, Some(self.costs.forward)),
+            step(
+                behind.step(node.direction.turn_right()),
+                Action::ForwardLeft,
+                diagonal,
+            ),
+            step(
+                behind.step(node.direction.turn_left()),
+                Action::ForwardRight,
+                diagonal,
+            ),
+            turn(
+                node.direction.turn_right(),
+                Action::TurnLeft,
+                Some(self.costs.turn),
+            ),
+            turn(
+                node.direction.turn_left(),
+                Action::TurnRight,
+                Some(self.costs.turn),
+            ),
+            turn(node.direction.reverse(), Action::UTurn, self.costs.u_turn),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    #[cfg(not(feature = "grid-search"))]
+    fn weighted_successors(&self, node: &Node) -> impl Iterator<Item = (Node, usize)> + '_ {
+        self.successors(*node).map(|(node, _, cost)| (node, cost))
+    }
+
+    /// A lower bound on the cost of reaching the end, for A*.
+    #[cfg(not(feature = "grid-search"))]
+    fn heuristic(&self, node: &Node) -> usize {
+        let per_tile = match self.costs.diagonal {
+            Some(diagonal) => self.costs.forward.min(diagonal / 2),
+            None => self.costs.forward,
+        };
+        node.position.manhattan_distance(self.end) as usize * per_tile
+    }
+
+    fn path(&self, nodes: Vec<Node>, cost: usize) -> Path {
+        let actions = nodes
+            .windows(2)
+            .filter_map(|pair| {
+                self.successors(pair[0])
+                    .filter(|&(node, _, _)| node == pair[1])
+                    .min_by_key(|&(_, _, cost)| cost)
+                    .map(|(_, action, _)| action)
+            })
+            .collect();
+        Path {
+            nodes,
+            actions,
+            cost,
+        }
+    }
+
+    /// The lowest score of any path from the start to the end.
+    #[cfg(not(feature = "grid-search"))]
+    pub fn best_score(&self) -> Result<usize, Error> {
+        dijkstra(
+            &self.start,
+            |node| self.weighted_successors(node),
+            |node| node.position == self.end,
+        )
+        .ok_or(Error::NoPathToEndFound)
+        .map(|(_, cost)| cost)
+    }
+
+    /// The lowest score of any path from the start to the end.
+    #[cfg(feature = "grid-search")]
+    pub fn best_score(&self) -> Result<usize, Error> {
+        search::dijkstra(
+            self.grid.len() * 4,
+            self.index(self.start),
+            |index| self.indexed_successors(index),
+            |index| self.node(index).position == self.end,
+        )
+        .ok_or(Error::NoPathToEndFound)
+        .map(|(_, cost)| cost)
+    }
+
+    /// Every path with the lowest score, generated lazily as there may be very many.
+    #[cfg(not(feature = "grid-search"))]
+    pub fn best_paths(&self) -> Result<impl Iterator<Item = Path> + '_, Error> {
+        let (solutions, cost) = astar_bag(
+            &self.start,
+            |node| self.weighted_successors(node),
+            |node| self.heuristic(node),
+            |node| node.position == self.end,
+        )
+        .ok_or(Error::NoPathToEndFound)?;
+        Ok(solutions.map(move |nodes| self.path(nodes, cost)))
+    }
+
+    /// Every path with the lowest score, generated lazily as there may be very many.
+    #[cfg(feature = "grid-search")]
+    pub fn best_paths(&self) -> Result<impl Iterator<Item = Path> + '_, Error> {
+        let (from, to) = self.distance_fields();
+        let start = self.index(self.start);
+        let cost = to.get(start).ok_or(Error::NoPathToEndFound)?;
+        let paths = search::shortest_paths(start, from, to, |index| self.indexed_successors(index));
+        Ok(paths.map(move |indices| {
+            let nodes = indices.into_iter().map(|index| self.node(index)).collect();
+            self.path(nodes, cost)
+        }))
+    }
+
+    /// The tiles on at least one path with the lowest score.
+    #[cfg(not(feature = "grid-search"))]
+    pub fn best_tiles(&self) -> Result<HashSet<Point>, Error> {
+        Ok(self
+            .best_paths()?
+            .flat_map(|path| path.nodes.into_iter().map(|node| node.position))
+            .collect())
+    }
+
+    /// The tiles on at least one path with the lowest score.
+    #[cfg(feature = "grid-search")]
+    pub fn best_tiles(&self) -> Result<HashSet<Point>, Error> {
+        let (from, to) = self.distance_fields();
+        let best = to
+            .get(self.index(self.start))
+            .ok_or(Error::NoPathToEndFound)?;
+        Ok(from
+            .iter()
+            .filter(|&(index, distance)| {
+                best.checked_sub(distance)
+                    .is_some_and(|rest| to.get(index) == Some(rest))
+            })
+            .map(|(index, _)| self.node(index).position)
+            .collect())
+    }
+
+    #[cfg(feature = "grid-search")]
+    fn index(&self, node: Node) -> usize {
+        let position = self
+            .grid
+            .index_of(node.position)
+            .expect("node outside the maze");
+        position * 4 + node.direction as usize
+    }
+
+    #[cfg(feature = "grid-search")]
+    fn node(&self, index: usize) -> Node {
+        Node {
+            position: self.grid.point_of(index / 4),
+            direction: Direction::ALL[index % 4],
+        }
+    }
+
+    #[cfg(feature = "grid-search")]
+    fn indexed_successors(&self, index: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.successors(self.node(index))
+            .map(|(node, _, cost)| (self.index(node), cost))
+    }
+
+    /// The distances from the start to every node, and from every node to the end.
+    #[cfg(feature = "grid-search")]
+    fn distance_fields(&self) -> (Distances, Distances) {
+        let len = self.grid.len() * 4;
+        let from = search::dijkstra_all(len, [self.index(self.start)], |index| {
+            self.indexed_successors(index)
+        });
+        let ends = Direction::ALL.map(|direction| {
+            self.index(Node {
+                position: self.end,
+                direction,
+            })
+        });
+        let to = search::dijkstra_all(len, ends, |index| {
+            self.predecessors(self.node(index))
+                .map(|(node, _, cost)| (self.index(node), cost))
+        });
+        (from, to)
+    }
+
+    /// The maze with `O` on the given tiles, other than the start and end.
+    pub fn render_tiles(&self, tiles: &HashSet<Point>) -> String {
+        let mut grid = self.grid.clone();
+        for &tile in tiles {
+            if let Some(cell @ b'.') = grid.get_mut(tile) {
+                *cell = b'O';
+            }
+        }
+        grid.to_string()
+    }
+
+    /// The maze with an arrow on each tile the path leaves, in the direction it
+    /// leaves in.
+    pub fn render_path(&self, path: &Path) -> String {
+        let mut grid = self.grid.clone();
+        for (node, action) in path.nodes.iter().zip(&path.actions) {
+            let turning = matches!(action, Action::TurnLeft | Action::TurnRight | Action::UTurn);
+            if let (false, Some(cell @ b'.')) = (turning, grid.get_mut(node.position)) {
+                *cell = node.direction.arrow();
+            }
+        }
+        grid.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+    const EXAMPLE2: &str = include_str!("../example2.txt");
+
+    #[test]
+    fn best_paths() {
+        let maze = Maze::parse(EXAMPLE).unwrap();
+        let paths = maze.best_paths().unwrap().collect::<Vec<_>>();
+        assert_eq!(paths.len(), 3);
+        for path in &paths {
+            assert_eq!(path.cost, 7036);
+            assert_eq!(path.actions.len(), path.nodes.len() - 1);
+            assert_eq!(path.nodes[0], maze.start());
+            assert_eq!(path.nodes.last().unwrap().position, maze.end());
+        }
+        assert_eq!(maze.best_tiles().unwrap().len(), 45);
+        assert_eq!(Maze::parse(EXAMPLE2).unwrap().best_score(), Ok(11048));
+    }
+
+    #[test]
+    fn render() {
+        let maze = Maze::parse("#####\n#..E#\n#S#.#\n#####").unwrap();
+        let path = maze.best_paths().unwrap().next().unwrap();
+        assert_eq!(
+            path.actions
+                .iter()
+                .map(|action| action.to_string())
+                .collect::<String>(),
+            "LFRFF"
+        );
+        assert_eq!(path.cost, 2003);
+        assert_eq!(maze.render_path(&path), "#####\n#>>E#\n#S#.#\n#####\n");
+        assert_eq!(
+            maze.render_tiles(&maze.best_tiles().unwrap()),
+            "#####\n#OOE#\n#S#.#\n#####\n"
+        );
+    }
+
+    #[test]
+    fn cost_models() {
+        let score = |maze: &str, costs: CostModel| {
+            let maze = Maze::parse(maze).unwrap().with_costs(costs).unwrap();
+            let path = maze.best_paths().unwrap().next().unwrap();
+            let actions = path.actions.iter().map(|action| action.to_string());
+            (maze.best_score().unwrap(), actions.collect::<String>())
+        };
+        let cheap_turns = CostModel {
+            turn: 1,
+            ..Default::default()
+        };
+        assert_eq!(score(EXAMPLE, cheap_turns.clone()).0, 38);
+
+        let behind = "#####\n#E.S#\n#####";
+        assert_eq!(score(behind, CostModel::default()).0, 2002);
+        let u_turn = CostModel {
+            u_turn: Some(1500),
+            ..Default::default()
+        };
+        assert_eq!(score(behind, u_turn), (1502, "UFF".to_string()));
+
+        let corner = "#####\n#..E#\n#S#.#\n#####";
+        let diagonal = CostModel {
+            diagonal: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(score(corner, diagonal), (4, "lF".to_string()));
+
+        let swamp = "#######\n#S~~~E#\n#.###.#\n#.....#\n#######";
+        assert_eq!(score(swamp, cheap_turns.clone()).0, 4);
+        let terrain = CostModel {
+            terrain: HashMap::from([(b'~', 5)]),
+            ..cheap_turns
+        };
+        assert_eq!(score(swamp, terrain), (11, "RFFLFFFFLFF".to_string()));
+    }
+
+    #[test]
+    fn predecessors() {
+        let costs = CostModel {
+            u_turn: Some(1500),
+            diagonal: Some(3),
+            terrain: HashMap::from([(b'~', 5)]),
+            ..Default::default()
+        };
+        let maze = Maze::parse("#####\n#.~E#\n#S..#\n#~#.#\n#####")
+            .unwrap()
+            .with_costs(costs)
+            .unwrap();
+        let nodes = maze.grid().points().flat_map(|position| {
+            Direction::ALL.map(|direction| Node {
+                position,
+                direction,
+            })
+        });
+        for node in nodes.filter(|node| maze.grid()[node.position] != b'#') {
+            for (next, action, cost) in maze.successors(node) {
+                assert!(maze
+                    .predecessors(next)
+                    .any(|edge| edge == (node, action, cost)));
+            }
+            for (previous, action, cost) in maze.predecessors(node) {
+                assert!(maze
+                    .successors(previous)
+                    .any(|edge| edge == (node, action, cost)));
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Maze::parse("#S#"), Err(Error::StartOrEndNotFound));
+        let maze = Maze::parse("#S#E#").unwrap();
+        assert_eq!(maze.best_score(), Err(Error::NoPathToEndFound));
+        assert!(maze.best_paths().is_err());
+
+        let free_turns = CostModel {
+            turn: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            maze.clone().with_costs(free_turns),
+            Err(Error::ZeroCost("turn".to_string()))
+        );
+        let free_grass = CostModel {
+            terrain: HashMap::from([(b'"', 0)]),
+            ..Default::default()
+        };
+        assert_eq!(
+            maze.with_costs(free_grass),
+            Err(Error::ZeroCost("terrain \"".to_string()))
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use day17::{
+    debugger::{Command, Debugger},
+    vm::disassemble,
+};
+use std::{
+    error::Error,
+    fs,
+    io::{self, BufRead, Write},
+};
+
+const USAGE: &str = "\
+Usage: day17 debug <file>
+       day17 disasm <file>
+
+The file holds a program in puzzle input format or as assembly source.";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let (command, path) = match args.as_slice() {
+        [command, path] => (command.as_str(), path),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    let mut debugger = Debugger::load(&fs::read_to_string(path)?)?;
+    match command {
+        "debug" => repl(&mut debugger),
+        "disasm" => {
+            println!("{}", disassemble(debugger.machine().program()));
+            Ok(())
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
+}
+
+fn repl(debugger: &mut Debugger) -> Result<(), Box<dyn Error>> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut lines = stdin.lock().lines();
+    loop {
+        write!(stdout, "(day17) ")?;
+        stdout.flush()?;
+        let Some(line) = lines.next().transpose()? else {
+            return Ok(());
+        };
+        match Command::parse(&line) {
+            Ok(Command::Quit) => return Ok(()),
+            Ok(command) => match debugger.execute(command) {
+                Ok(response) => write!(stdout, "{response}")?,
+                Err(error) => writeln!(stdout, "Error: {error}")?,
+            },
+            Err(error) => writeln!(stdout, "Error: {error}")?,
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use aoc_common::input;
 use day17::{part2, INPUT};
 
-fn main() -> Result<(), part2::Error> {
-    println!("{:?}", part2::solve(INPUT)?);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let input = input::load(17, INPUT)?;
+    println!("{:?}", part2::solve(&input)?);
     Ok(())
 }
@@ -0,0 +1,313 @@
+use crate::vm::{self, assemble, disassemble, Machine, Registers, StopReason};
+use std::fmt::Write;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Unknown command: {0} (try help)")]
+    UnknownCommand(String),
+    #[error("Missing argument: {0}")]
+    MissingArgument(&'static str),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("{0}")]
+    Vm(#[from] vm::Error),
+    #[error("Neither puzzle input ({input}) nor assembly ({assembly})")]
+    Unparsable {
+        input: vm::Error,
+        assembly: vm::Error,
+    },
+}
+
+pub const HELP: &str = "\
+step [n]        execute n instructions (default 1), printing a trace
+continue        run until halt, breakpoint or step limit
+break <ip>      set a breakpoint at an instruction address
+delete <ip>     remove a breakpoint
+regs            show the registers
+set <reg> <n>   set register a, b, c or ip
+limit [n]       cap the number of steps, or remove the cap
+list            disassemble the program
+out             show the output so far
+reset           restart with the initial registers
+quit            exit the debugger";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Step(usize),
+    Continue,
+    Break(usize),
+    Delete(usize),
+    Registers,
+    Set(char, usize),
+    Limit(Option<usize>),
+    List,
+    Output,
+    Reset,
+    Help,
+    Quit,
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Self, Error> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(Self::Step(1));
+        };
+        match command {
+            "s" | "step" => Ok(Self::Step(number(words.next())?.unwrap_or(1))),
+            "c" | "continue" => Ok(Self::Continue),
+            "b" | "break" => Ok(Self::Break(
+                number(words.next())?.ok_or(Error::MissingArgument("ip"))?,
+            )),
+            "d" | "delete" => Ok(Self::Delete(
+                number(words.next())?.ok_or(Error::MissingArgument("ip"))?,
+            )),
+            "r" | "regs" => Ok(Self::Registers),
+            "set" => {
+                let register = match words.next() {
+                    Some(name @ ("a" | "b" | "c" | "ip" | "A" | "B" | "C" | "IP")) => {
+                        name.to_ascii_lowercase().chars().next().unwrap_or('i')
+                    }
+                    Some(name) => return Err(Error::InvalidArgument(name.to_string())),
+                    None => return Err(Error::MissingArgument("register")),
+                };
+                let value = number(words.next())?.ok_or(Error::MissingArgument("value"))?;
+                Ok(Self::Set(register, value))
+            }
+            "limit" => Ok(Self::Limit(number(words.next())?)),
+            "l" | "list" | "dis" => Ok(Self::List),
+            "o" | "out" => Ok(Self::Output),
+            "reset" => Ok(Self::Reset),
+            "h" | "help" | "?" => Ok(Self::Help),
+            "q" | "quit" | "exit" => Ok(Self::Quit),
+            _ => Err(Error::UnknownCommand(command.to_string())),
+        }
+    }
+}
+
+fn number(word: Option<&str>) -> Result<Option<usize>, Error> {
+    word.map(|word| {
+        word.parse()
+            .map_err(|_| Error::InvalidArgument(word.to_string()))
+    })
+    .transpose()
+}
+
+/// An interactive front end for a [`Machine`], driven by text commands.
+pub struct Debugger {
+    machine: Machine,
+    initial: Registers,
+}
+
+impl Debugger {
+    pub fn new(machine: Machine) -> Self {
+        let initial = machine.registers;
+        Self { machine, initial }
+    }
+
+    /// Loads a program in puzzle input format, or as assembly source if it has
+    /// no `Register` or `Program:` lines.
+    pub fn load(source: &str) -> Result<Self, Error> {
+        let input = match Machine::parse(source) {
+            Ok(machine) => return Ok(Self::new(machine)),
+            Err(error) => error,
+        };
+        let has_header = source.lines().any(|line| {
+            let line = line.trim_start();
+            line.starts_with("Register ") || line.starts_with("Program:")
+        });
+        if has_header {
+            return Err(input.into());
+        }
+        let program = assemble(source).map_err(|assembly| Error::Unparsable { input, assembly })?;
+        Ok(Self::new(Machine::new(program, Registers::default())))
+    }
+
+    pub fn machine(&self) -> &Machine {
+        &self.machine
+    }
+
+    /// Executes a command and returns the text to show the user.
+    pub fn execute(&mut self, command: Command) -> Result<String, Error> {
+        let mut response = String::new();
+        match command {
+            Command::Step(count) => {
+                for _ in 0..count {
+                    let (steps, limit) = (self.machine.steps(), self.machine.step_limit());
+                    if limit.is_some_and(|limit| steps >= limit) {
+                        writeln!(response, "Step limit reached").unwrap();
+                        break;
+                    }
+                    match self.machine.step()? {
+                        Some(step) => writeln!(response, "{step}").unwrap(),
+                        None => {
+                            writeln!(response, "Halted").unwrap();
+                            break;
+                        }
+                    }
+                }
+            }
+            Command::Continue => {
+                let reason = self.machine.run()?;
+                match reason {
+                    StopReason::Halted => writeln!(response, "Halted"),
+                    StopReason::Breakpoint(ip) => writeln!(response, "Breakpoint at {ip:03}"),
+                    StopReason::StepLimit => {
+                        writeln!(response, "Step limit reached")
+                    }
+                }
+                .unwrap();
+                writeln!(response, "{}", self.machine.registers).unwrap();
+            }
+            Command::Break(ip) => {
+                self.machine.add_breakpoint(ip);
+                writeln!(response, "Breakpoint set at {ip:03}").unwrap();
+            }
+            Command::Delete(ip) => {
+                if self.machine.remove_breakpoint(ip) {
+                    writeln!(response, "Breakpoint removed from {ip:03}").unwrap();
+                } else {
+                    writeln!(response, "No breakpoint at {ip:03}").unwrap();
+                }
+            }
+            Command::Registers => {
+                writeln!(
+                    response,
+                    "{} steps={}",
+                    self.machine.registers,
+                    self.machine.steps()
+                )
+                .unwrap();
+            }
+            Command::Set(register, value) => {
+                let registers = &mut self.machine.registers;
+                match register {
+                    'a' => registers.a = value,
+                    'b' => registers.b = value,
+                    'c' => registers.c = value,
+                    _ => registers.ip = value,
+                }
+                writeln!(response, "{registers}").unwrap();
+            }
+            Command::Limit(limit) => {
+                self.machine.set_step_limit(limit);
+                match limit {
+                    Some(limit) => writeln!(response, "Step limit set to {limit}"),
+                    None => writeln!(response, "Step limit removed"),
+                }
+                .unwrap();
+            }
+            Command::List => {
+                let breakpoints = self.machine.breakpoints().collect::<Vec<_>>();
+                for (index, line) in disassemble(self.machine.program()).lines().enumerate() {
+                    let address = index * 2;
+                    let marker = if address == self.machine.registers.ip {
+                        '>'
+                    } else {
+                        ' '
+                    };
+                    let breakpoint = if breakpoints.contains(&address) {
+                        '*'
+                    } else {
+                        ' '
+                    };
+                    writeln!(response, "{marker}{breakpoint} {line}").unwrap();
+                }
+            }
+            Command::Output => {
+                let output = self
+                    .machine
+                    .output()
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                writeln!(response, "{output}").unwrap();
+            }
+            Command::Reset => {
+                self.machine.reset(self.initial);
+                writeln!(response, "{}", self.machine.registers).unwrap();
+            }
+            Command::Help => writeln!(response, "{HELP}").unwrap(),
+            Command::Quit => {}
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(Command::parse(""), Ok(Command::Step(1)));
+        assert_eq!(Command::parse("step 5"), Ok(Command::Step(5)));
+        assert_eq!(Command::parse("b 4"), Ok(Command::Break(4)));
+        assert_eq!(Command::parse("set A 12"), Ok(Command::Set('a', 12)));
+        assert_eq!(Command::parse("limit"), Ok(Command::Limit(None)));
+        assert_eq!(Command::parse("break"), Err(Error::MissingArgument("ip")));
+        assert_eq!(
+            Command::parse("jump"),
+            Err(Error::UnknownCommand("jump".to_string()))
+        );
+    }
+
+    #[test]
+    fn session() {
+        let mut debugger = Debugger::load(EXAMPLE).unwrap();
+        let mut run = |line| debugger.execute(Command::parse(line).unwrap()).unwrap();
+
+        assert_eq!(run("step"), "000: adv 1  A=364 B=0 C=0 IP=002\n");
+        assert_eq!(run("break 4"), "Breakpoint set at 004\n");
+        assert_eq!(run("continue"), "Breakpoint at 004\nA=364 B=0 C=0 IP=004\n");
+        assert_eq!(run("out"), "4\n");
+        assert!(run("list").contains(">* 004: jnz 0"));
+        run("delete 4");
+        assert_eq!(run("continue"), "Halted\nA=0 B=0 C=0 IP=006\n");
+        assert_eq!(run("out"), "4,6,3,5,6,3,5,2,1,0\n");
+        run("reset");
+        run("set a 8");
+        run("continue");
+        assert_eq!(run("out"), "4,2,1,0\n");
+        run("reset");
+        run("limit 2");
+        assert_eq!(
+            run("step 1000"),
+            "000: adv 1  A=364 B=0 C=0 IP=002\n\
+             002: out A  A=364 B=0 C=0 IP=004 -> 4\n\
+             Step limit reached\n"
+        );
+    }
+
+    #[test]
+    fn load_assembly() {
+        let mut debugger = Debugger::load("bst A\nout B").unwrap();
+        let mut run = |line| debugger.execute(Command::parse(line).unwrap()).unwrap();
+        run("set a 13");
+        run("continue");
+        assert_eq!(run("out"), "5\n");
+    }
+
+    #[test]
+    fn load_errors() {
+        let input = EXAMPLE.replace("Register B: 0", "Register B: x");
+        assert!(matches!(
+            Debugger::load(&input).err(),
+            Some(Error::Vm(vm::Error::FailedToParseNumber(_)))
+        ));
+        assert_eq!(
+            Debugger::load("bst A\nfoo 2").err(),
+            Some(Error::Unparsable {
+                input: vm::Error::InvalidRegisterPrefix('A'),
+                assembly: vm::Error::UnknownMnemonic {
+                    line: 2,
+                    mnemonic: "foo".to_string()
+                },
+            })
+        );
+    }
+}
@@ -1,7 +1,17 @@
+use aoc_common::{Solution, Solver};
+
+pub mod debugger;
 pub mod part1;
 pub mod part2;
+pub mod quine;
+pub mod vm;
 
 #[cfg(input_txt)]
 pub const INPUT: &str = include_str!("../input.txt");
 #[cfg(not(input_txt))]
 pub const INPUT: &str = include_str!("../example.txt");
+
+pub static SOLUTIONS: [&dyn Solution; 2] = [
+    &Solver::new(17, 1, part1::solve),
+    &Solver::new(17, 2, part2::solve),
+];
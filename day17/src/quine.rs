@@ -0,0 +1,529 @@
+//! Search for the smallest value of register A that makes a program output itself.
+//!
+//! The program is executed symbolically: every bit of every register is an affine
+//! function over GF(2) of the bits of the initial A. Each output adds linear
+//! constraints on those bits, and whenever execution needs a concrete value (a
+//! shift amount or the `jnz` test) the search branches on the undetermined bits.
+//! This makes no assumption about the shape of the program.
+
+use crate::vm::{ComboOperand, Instruction, Machine, Registers};
+use std::ops::BitXor;
+use thiserror::Error;
+
+const BITS: usize = usize::BITS as usize;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("No value of register A makes the program output itself")]
+    NoSolution,
+    #[error("Search gave up after exploring {0} states")]
+    StateLimit(usize),
+    #[error("Search gave up on paths running longer than {0} steps")]
+    StepLimit(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum number of branches explored in total.
+    pub states: usize,
+    /// The maximum number of instructions executed along one branch.
+    pub steps: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            states: 1_000_000,
+            steps: 100_000,
+        }
+    }
+}
+
+/// Finds the smallest value of register A for which the machine outputs its own program.
+///
+/// Registers B and C keep their values from the machine.
+pub fn find(machine: &Machine) -> Result<usize, Error> {
+    find_with(machine, Limits::default())
+}
+
+pub fn find_with(machine: &Machine, limits: Limits) -> Result<usize, Error> {
+    let mut search = Search {
+        program: machine.program(),
+        limits,
+        states: 0,
+        truncated: false,
+    };
+    // Trying each bit length in turn means the first solution found is in the
+    // smallest possible range, and the minimum within that range is exact. That
+    // only holds if no branch so far was cut off by the step limit, as one at
+    // this width or a narrower one may have held a smaller solution.
+    for width in 0..=BITS {
+        if let Some(a) = search.run(width, machine.registers)? {
+            if search.truncated {
+                return Err(Error::StepLimit(limits.steps));
+            }
+            return Ok(a);
+        }
+    }
+    if search.truncated {
+        Err(Error::StepLimit(limits.steps))
+    } else {
+        Err(Error::NoSolution)
+    }
+}
+
+/// A bit that is the parity of the initial A bits in `mask`, XOR `value`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Bit {
+    mask: u64,
+    value: bool,
+}
+
+impl Bit {
+    const ZERO: Bit = Bit::constant(false);
+    const ONE: Bit = Bit::constant(true);
+
+    const fn constant(value: bool) -> Self {
+        Self { mask: 0, value }
+    }
+
+    fn variable(index: usize) -> Self {
+        Self {
+            mask: 1 << index,
+            value: false,
+        }
+    }
+
+    fn is_constant(&self) -> bool {
+        self.mask == 0
+    }
+}
+
+impl BitXor for Bit {
+    type Output = Bit;
+
+    fn bitxor(self, rhs: Bit) -> Bit {
+        Bit {
+            mask: self.mask ^ rhs.mask,
+            value: self.value ^ rhs.value,
+        }
+    }
+}
+
+type Word = [Bit; BITS];
+
+fn constant(value: usize) -> Word {
+    std::array::from_fn(|index| Bit::constant(value >> index & 1 == 1))
+}
+
+fn xor(a: &Word, b: &Word) -> Word {
+    std::array::from_fn(|index| a[index] ^ b[index])
+}
+
+fn shift_right(word: &Word, amount: usize) -> Word {
+    std::array::from_fn(|index| {
+        index
+            .checked_add(amount)
+            .and_then(|index| word.get(index))
+            .copied()
+            .unwrap_or(Bit::ZERO)
+    })
+}
+
+fn modulo_8(word: &Word) -> Word {
+    std::array::from_fn(|index| if index < 3 { word[index] } else { Bit::ZERO })
+}
+
+/// Linear constraints on the bits of the initial A, in reduced row echelon form.
+///
+/// Each row asserts that its bit evaluates to zero, and is stored at the index of
+/// its lowest variable.
+#[derive(Debug, Clone)]
+struct System {
+    rows: [Option<Bit>; BITS],
+}
+
+impl System {
+    fn new() -> Self {
+        Self { rows: [None; BITS] }
+    }
+
+    /// Rewrites a bit so that it refers to no variable that is a row pivot.
+    fn reduce(&self, mut bit: Bit) -> Bit {
+        for (index, row) in self.rows.iter().enumerate() {
+            if let Some(row) = row {
+                if bit.mask >> index & 1 == 1 {
+                    bit = bit ^ *row;
+                }
+            }
+        }
+        bit
+    }
+
+    /// Asserts that a bit is zero, returning false if that contradicts the system.
+    fn assert_zero(&mut self, bit: Bit) -> bool {
+        let bit = self.reduce(bit);
+        if bit.is_constant() {
+            return !bit.value;
+        }
+        let pivot = bit.mask.trailing_zeros() as usize;
+        for row in self.rows.iter_mut().flatten() {
+            if row.mask >> pivot & 1 == 1 {
+                *row = *row ^ bit;
+            }
+        }
+        self.rows[pivot] = Some(bit);
+        true
+    }
+
+    fn assert_equal(&mut self, bit: Bit, value: bool) -> bool {
+        self.assert_zero(bit ^ Bit::constant(value))
+    }
+
+    /// The smallest A satisfying every constraint.
+    ///
+    /// Every row's other variables are higher than its pivot and are free, so
+    /// choosing bits from the top down, zero for each free one, is optimal.
+    fn minimum(&self) -> usize {
+        let mut a = 0u64;
+        for index in (0..BITS).rev() {
+            if let Some(row) = self.rows[index] {
+                let others = row.mask & !(1 << index) & a;
+                if (others.count_ones() % 2 == 1) != row.value {
+                    a |= 1 << index;
+                }
+            }
+        }
+        a as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    a: Word,
+    b: Word,
+    c: Word,
+    ip: usize,
+    output: usize,
+    steps: usize,
+    system: System,
+    /// Register values at each jump taken since the last output.
+    jumps: Vec<(usize, [Word; 3])>,
+}
+
+enum Outcome {
+    Continue,
+    /// Execution depends on a bit that is not yet determined.
+    Branch(Bit),
+    Prune,
+    Halt,
+}
+
+/// A concrete value or the bit that must be decided first.
+type Resolved<T> = Result<T, Bit>;
+
+impl State {
+    fn reduce(&self, word: &Word) -> Word {
+        std::array::from_fn(|index| self.system.reduce(word[index]))
+    }
+
+    fn combo(&self, operand: ComboOperand) -> Word {
+        match operand {
+            ComboOperand::Literal(value) => constant(value as usize),
+            ComboOperand::RegisterA => self.a,
+            ComboOperand::RegisterB => self.b,
+            ComboOperand::RegisterC => self.c,
+        }
+    }
+
+    /// The value of a shift amount, where anything of at least 64 shifts out every bit.
+    fn shift_amount(&self, operand: ComboOperand) -> Resolved<usize> {
+        let word = self.reduce(&self.combo(operand));
+        let saturating = BITS.trailing_zeros() as usize;
+        if word[saturating..].contains(&Bit::ONE) {
+            return Ok(BITS);
+        }
+        if let Some(bit) = word.iter().rev().find(|bit| !bit.is_constant()) {
+            return Err(*bit);
+        }
+        Ok(word
+            .iter()
+            .enumerate()
+            .filter(|(_, bit)| bit.value)
+            .map(|(index, _)| 1 << index)
+            .sum())
+    }
+
+    fn is_non_zero(&self, word: &Word) -> Resolved<bool> {
+        let word = self.reduce(word);
+        if word.contains(&Bit::ONE) {
+            return Ok(true);
+        }
+        match word.iter().rev().find(|bit| !bit.is_constant()) {
+            Some(bit) => Err(*bit),
+            None => Ok(false),
+        }
+    }
+
+    fn step(&mut self, program: &[u8], limits: &Limits) -> Outcome {
+        let Some(&[opcode, operand]) = program.get(self.ip..self.ip + 2) else {
+            return Outcome::Halt;
+        };
+        if self.steps >= limits.steps {
+            return Outcome::Prune;
+        }
+        // An instruction the machine cannot execute means this branch crashes.
+        let Ok(instruction) = Instruction::decode(opcode, operand) else {
+            return Outcome::Prune;
+        };
+        match instruction {
+            Instruction::Adv(operand) => match self.shift_amount(operand) {
+                Ok(amount) => self.a = shift_right(&self.a, amount),
+                Err(bit) => return Outcome::Branch(bit),
+            },
+            Instruction::Bxl(value) => self.b = xor(&self.b, &constant(value as usize)),
+            Instruction::Bst(operand) => self.b = modulo_8(&self.combo(operand)),
+            Instruction::Jnz(target) => match self.is_non_zero(&self.a) {
+                Ok(true) => {
+                    // Returning to an earlier state without any output in between
+                    // means the program loops forever.
+                    let jump = (target as usize, [self.a, self.b, self.c]);
+                    if self.jumps.contains(&jump) {
+                        return Outcome::Prune;
+                    }
+                    self.jumps.push(jump);
+                    self.ip = target as usize;
+                    self.steps += 1;
+                    return Outcome::Continue;
+                }
+                Ok(false) => {}
+                Err(bit) => return Outcome::Branch(bit),
+            },
+            Instruction::Bxc(_) => self.b = xor(&self.b, &self.c),
+            Instruction::Out(operand) => {
+                let Some(&expected) = program.get(self.output) else {
+                    return Outcome::Prune;
+                };
+                let word = self.combo(operand);
+                for (index, bit) in word.iter().take(3).enumerate() {
+                    if !self.system.assert_equal(*bit, expected >> index & 1 == 1) {
+                        return Outcome::Prune;
+                    }
+                }
+                self.output += 1;
+                self.jumps.clear();
+            }
+            Instruction::Bdv(operand) => match self.shift_amount(operand) {
+                Ok(amount) => self.b = shift_right(&self.a, amount),
+                Err(bit) => return Outcome::Branch(bit),
+            },
+            Instruction::Cdv(operand) => match self.shift_amount(operand) {
+                Ok(amount) => self.c = shift_right(&self.a, amount),
+                Err(bit) => return Outcome::Branch(bit),
+            },
+        }
+        self.ip += 2;
+        self.steps += 1;
+        Outcome::Continue
+    }
+}
+
+struct Search<'a> {
+    program: &'a [u8],
+    limits: Limits,
+    states: usize,
+    truncated: bool,
+}
+
+impl Search<'_> {
+    /// The smallest solution whose highest set bit is `width - 1`, if there is one.
+    fn run(&mut self, width: usize, registers: Registers) -> Result<Option<usize>, Error> {
+        let mut system = System::new();
+        if width > 0 {
+            system.assert_equal(Bit::variable(width - 1), true);
+        }
+        let initial = State {
+            a: std::array::from_fn(|index| {
+                if index < width {
+                    Bit::variable(index)
+                } else {
+                    Bit::ZERO
+                }
+            }),
+            b: constant(registers.b),
+            c: constant(registers.c),
+            ip: 0,
+            output: 0,
+            steps: 0,
+            system,
+            jumps: vec![],
+        };
+
+        let mut best = None;
+        let mut stack = vec![initial];
+        while let Some(mut state) = stack.pop() {
+            self.states += 1;
+            if self.states > self.limits.states {
+                return Err(Error::StateLimit(self.limits.states));
+            }
+            loop {
+                match state.step(self.program, &self.limits) {
+                    Outcome::Continue => {}
+                    Outcome::Branch(bit) => {
+                        let mut one = state.clone();
+                        if one.system.assert_equal(bit, true) {
+                            stack.push(one);
+                        }
+                        if state.system.assert_equal(bit, false) {
+                            stack.push(state);
+                        }
+                        break;
+                    }
+                    Outcome::Prune => {
+                        self.truncated |= state.steps >= self.limits.steps;
+                        break;
+                    }
+                    Outcome::Halt => {
+                        if state.output == self.program.len() {
+                            let a = state.system.minimum();
+                            best = Some(best.map_or(a, |best: usize| best.min(a)));
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::assemble;
+
+    fn outputs_itself(program: &[u8], a: usize) -> bool {
+        let mut machine = Machine::new(
+            program.to_vec(),
+            Registers {
+                a,
+                ..Default::default()
+            },
+        );
+        machine.set_step_limit(Some(1_000));
+        machine.run().is_ok() && machine.output() == program
+    }
+
+    fn brute_force(program: &[u8], limit: usize) -> Option<usize> {
+        (0..limit).find(|&a| outputs_itself(program, a))
+    }
+
+    #[test]
+    fn example() {
+        let machine = Machine::parse(include_str!("../example2.txt")).unwrap();
+        assert_eq!(find(&machine), Ok(117440));
+    }
+
+    #[test]
+    fn typical_input_shape() {
+        let program = vec![2, 4, 1, 1, 7, 5, 1, 5, 4, 0, 0, 3, 5, 5, 3, 0];
+        let machine = Machine::new(program.clone(), Registers::default());
+        let a = find(&machine).unwrap();
+        assert!(outputs_itself(&program, a));
+    }
+
+    #[test]
+    fn other_program_shapes() {
+        // Outputs bits of A other than the lowest three.
+        let program = assemble("bdv 2\nout B\nadv 3\njnz 0").unwrap();
+        let machine = Machine::new(program.clone(), Registers::default());
+        assert_eq!(find(&machine), Ok(3550552));
+
+        // Shifts A by five bits per loop.
+        let program = assemble("adv 2\nout A\nadv 3\njnz 0").unwrap();
+        let machine = Machine::new(program.clone(), Registers::default());
+        let a = find(&machine).unwrap();
+        assert!(outputs_itself(&program, a));
+
+        // Small enough to check every smaller value.
+        let program = assemble("adv 1\nbst A\nout B\njnz 0").unwrap();
+        let machine = Machine::new(program.clone(), Registers::default());
+        assert_eq!(find(&machine).ok(), brute_force(&program, 1 << 16));
+    }
+
+    #[test]
+    fn carries_state_between_loops() {
+        // C is read before it is written, so each output also depends on the
+        // previous iteration and folding over the program in reverse fails.
+        let program = vec![2, 4, 1, 2, 4, 5, 0, 3, 7, 5, 1, 7, 5, 5, 3, 0];
+        let machine = Machine::new(program.clone(), Registers::default());
+        assert_eq!(find(&machine), Ok(205123390706983));
+        assert!(outputs_itself(&program, 205123390706983));
+    }
+
+    #[test]
+    fn no_solution() {
+        // Always outputs a 1 first, but the program starts with 5.
+        let program = assemble("out 1\nadv 3\njnz 0").unwrap();
+        let machine = Machine::new(program, Registers::default());
+        assert_eq!(find(&machine), Err(Error::NoSolution));
+    }
+
+    #[test]
+    fn limits() {
+        let program = assemble("bst A\nbxl 2\ncdv B\nbxc\nout B\nadv 2\njnz 0").unwrap();
+        let machine = Machine::new(program, Registers::default());
+        let limits = Limits {
+            states: 10,
+            ..Default::default()
+        };
+        assert_eq!(find_with(&machine, limits), Err(Error::StateLimit(10)));
+
+        let machine = Machine::parse(include_str!("../example2.txt")).unwrap();
+        let limits = Limits {
+            steps: 5,
+            ..Default::default()
+        };
+        assert_eq!(find_with(&machine, limits), Err(Error::StepLimit(5)));
+
+        // Branches from 46 bits up run past 50 steps, but the solution at 52
+        // bits does not.
+        let program = assemble("cdv B\nbst C\nout A\nadv B\njnz 0").unwrap();
+        let machine = Machine::new(program.clone(), Registers::default());
+        assert_eq!(find(&machine), Ok(3408864320744071));
+        let limits = Limits {
+            steps: 50,
+            ..Default::default()
+        };
+        assert_eq!(find_with(&machine, limits), Err(Error::StepLimit(50)));
+    }
+
+    #[test]
+    fn system() {
+        let mut system = System::new();
+        // a0 ^ a2 = 1, a1 = 1, a2 ^ a3 = 0
+        assert!(system.assert_equal(
+            Bit {
+                mask: 0b0101,
+                value: false
+            },
+            true
+        ));
+        assert!(system.assert_equal(Bit::variable(1), true));
+        assert!(system.assert_equal(
+            Bit {
+                mask: 0b1100,
+                value: false
+            },
+            false
+        ));
+        assert_eq!(system.minimum(), 0b0011);
+        assert!(!system.assert_equal(
+            Bit {
+                mask: 0b0010,
+                value: false
+            },
+            false
+        ));
+    }
+}
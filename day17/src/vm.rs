@@ -0,0 +1,629 @@
+use std::{collections::BTreeSet, fmt, num::ParseIntError};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Missing register {0} line")]
+    MissingRegisterLine(char),
+    #[error("Invalid register {0} prefix")]
+    InvalidRegisterPrefix(char),
+    #[error("Missing blank line")]
+    MissingBlankLine,
+    #[error("Missing program line")]
+    MissingProgramLine,
+    #[error("Invalid program prefix")]
+    InvalidProgramPrefix,
+    #[error("Failed to parse number: {0}")]
+    FailedToParseNumber(#[from] ParseIntError),
+    #[error("Invalid program value: {0}")]
+    InvalidProgramValue(u8),
+    #[error("Invalid opcode: {0}")]
+    InvalidOpcode(u8),
+    #[error("Reserved operand value")]
+    ReservedOperandValue,
+    #[error("Invalid operand")]
+    InvalidOperand,
+    #[error("Line {line}: unknown instruction {mnemonic}")]
+    UnknownMnemonic { line: usize, mnemonic: String },
+    #[error("Line {line}: invalid operand {operand}")]
+    InvalidAssemblyOperand { line: usize, operand: String },
+    #[error("Line {line}: missing operand")]
+    MissingAssemblyOperand { line: usize },
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Registers {
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+    /// Address of the next opcode in the program.
+    pub ip: usize,
+}
+
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "A={} B={} C={} IP={:03}",
+            self.a, self.b, self.c, self.ip
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComboOperand {
+    Literal(u8),
+    RegisterA,
+    RegisterB,
+    RegisterC,
+}
+
+impl ComboOperand {
+    pub fn decode(value: u8) -> Result<Self, Error> {
+        match value {
+            0..=3 => Ok(Self::Literal(value)),
+            4 => Ok(Self::RegisterA),
+            5 => Ok(Self::RegisterB),
+            6 => Ok(Self::RegisterC),
+            7 => Err(Error::ReservedOperandValue),
+            _ => Err(Error::InvalidOperand),
+        }
+    }
+
+    pub fn encode(&self) -> u8 {
+        match self {
+            Self::Literal(value) => *value,
+            Self::RegisterA => 4,
+            Self::RegisterB => 5,
+            Self::RegisterC => 6,
+        }
+    }
+
+    pub fn value(&self, registers: &Registers) -> usize {
+        match self {
+            Self::Literal(value) => *value as usize,
+            Self::RegisterA => registers.a,
+            Self::RegisterB => registers.b,
+            Self::RegisterC => registers.c,
+        }
+    }
+
+    fn parse(operand: &str) -> Option<Self> {
+        match operand {
+            "A" | "a" => Some(Self::RegisterA),
+            "B" | "b" => Some(Self::RegisterB),
+            "C" | "c" => Some(Self::RegisterC),
+            _ => match operand.parse() {
+                Ok(value @ 0..=3) => Some(Self::Literal(value)),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ComboOperand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Literal(value) => write!(f, "{value}"),
+            Self::RegisterA => write!(f, "A"),
+            Self::RegisterB => write!(f, "B"),
+            Self::RegisterC => write!(f, "C"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Adv(ComboOperand),
+    Bxl(u8),
+    Bst(ComboOperand),
+    /// Jump to an address in the program.
+    Jnz(u8),
+    /// The operand is ignored but kept so that programs re-encode exactly.
+    Bxc(u8),
+    Out(ComboOperand),
+    Bdv(ComboOperand),
+    Cdv(ComboOperand),
+}
+
+impl Instruction {
+    pub fn decode(opcode: u8, operand: u8) -> Result<Self, Error> {
+        if operand > 7 {
+            return Err(Error::InvalidOperand);
+        }
+        match opcode {
+            0 => Ok(Self::Adv(ComboOperand::decode(operand)?)),
+            1 => Ok(Self::Bxl(operand)),
+            2 => Ok(Self::Bst(ComboOperand::decode(operand)?)),
+            3 => Ok(Self::Jnz(operand)),
+            4 => Ok(Self::Bxc(operand)),
+            5 => Ok(Self::Out(ComboOperand::decode(operand)?)),
+            6 => Ok(Self::Bdv(ComboOperand::decode(operand)?)),
+            7 => Ok(Self::Cdv(ComboOperand::decode(operand)?)),
+            _ => Err(Error::InvalidOpcode(opcode)),
+        }
+    }
+
+    pub fn encode(&self) -> [u8; 2] {
+        match self {
+            Self::Adv(operand) => [0, operand.encode()],
+            Self::Bxl(value) => [1, *value],
+            Self::Bst(operand) => [2, operand.encode()],
+            Self::Jnz(target) => [3, *target],
+            Self::Bxc(ignored) => [4, *ignored],
+            Self::Out(operand) => [5, operand.encode()],
+            Self::Bdv(operand) => [6, operand.encode()],
+            Self::Cdv(operand) => [7, operand.encode()],
+        }
+    }
+
+    /// Executes the instruction, returning the value output by `out` if any.
+    pub fn apply(&self, registers: &mut Registers) -> Option<u8> {
+        let mut output = None;
+        registers.ip += 2;
+        match self {
+            Self::Adv(operand) => registers.a = shift(registers.a, operand.value(registers)),
+            Self::Bxl(value) => registers.b ^= *value as usize,
+            Self::Bst(operand) => registers.b = operand.value(registers) % 8,
+            Self::Jnz(target) => {
+                if registers.a != 0 {
+                    registers.ip = *target as usize;
+                }
+            }
+            Self::Bxc(_) => registers.b ^= registers.c,
+            Self::Out(operand) => output = Some((operand.value(registers) % 8) as u8),
+            Self::Bdv(operand) => registers.b = shift(registers.a, operand.value(registers)),
+            Self::Cdv(operand) => registers.c = shift(registers.a, operand.value(registers)),
+        }
+        output
+    }
+
+    /// A description of what the instruction does.
+    pub fn comment(&self) -> String {
+        match self {
+            Self::Adv(operand) => format!("Shift value in register A right by {operand}"),
+            Self::Bxl(value) => format!("Bitwise XOR value in register B with {value}"),
+            Self::Bst(operand) => format!("Write the value of {operand} modulo 8 to register B"),
+            Self::Jnz(target) => {
+                format!("Jump to instruction {target:03} if value in register A is not zero")
+            }
+            Self::Bxc(_) => {
+                "Bitwise XOR values in registers B and C, storing the result in register B"
+                    .to_string()
+            }
+            Self::Out(operand) => format!("Output value of {operand} modulo 8"),
+            Self::Bdv(operand) => {
+                format!("Write value in register A to register B shifted right by {operand}")
+            }
+            Self::Cdv(operand) => {
+                format!("Write value in register A to register C shifted right by {operand}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Self::Adv(operand) => format!("adv {operand}"),
+            Self::Bxl(value) => format!("bxl {value}"),
+            Self::Bst(operand) => format!("bst {operand}"),
+            Self::Jnz(target) => format!("jnz {target}"),
+            Self::Bxc(0) => "bxc".to_string(),
+            Self::Bxc(ignored) => format!("bxc {ignored}"),
+            Self::Out(operand) => format!("out {operand}"),
+            Self::Bdv(operand) => format!("bdv {operand}"),
+            Self::Cdv(operand) => format!("cdv {operand}"),
+        };
+        f.pad(&text)
+    }
+}
+
+fn shift(value: usize, amount: usize) -> usize {
+    u32::try_from(amount)
+        .ok()
+        .and_then(|amount| value.checked_shr(amount))
+        .unwrap_or(0)
+}
+
+/// Assembles a program from one instruction per line.
+///
+/// Comments start with `//` or `;`, and an address prefix such as `004:` is
+/// ignored, so the output of [`disassemble`] can be assembled again.
+pub fn assemble(source: &str) -> Result<Vec<u8>, Error> {
+    let mut program = vec![];
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.split("//").next().unwrap_or_default();
+        let line = line.split(';').next().unwrap_or_default();
+        let line = match line.split_once(':') {
+            Some((address, rest)) if address.trim().parse::<usize>().is_ok() => rest,
+            _ => line,
+        };
+        let mut tokens = line.split_whitespace();
+        let Some(mnemonic) = tokens.next() else {
+            continue;
+        };
+        let operand = tokens.next();
+        let invalid = |operand: &str| Error::InvalidAssemblyOperand {
+            line: line_number,
+            operand: operand.to_string(),
+        };
+        let combo = || {
+            let operand = operand.ok_or(Error::MissingAssemblyOperand { line: line_number })?;
+            ComboOperand::parse(operand).ok_or_else(|| invalid(operand))
+        };
+        let literal = |default: Option<u8>| match operand {
+            Some(operand) => match operand.parse() {
+                Ok(value @ 0..=7) => Ok(value),
+                _ => Err(invalid(operand)),
+            },
+            None => default.ok_or(Error::MissingAssemblyOperand { line: line_number }),
+        };
+        let instruction = match mnemonic.to_ascii_lowercase().as_str() {
+            "adv" => Instruction::Adv(combo()?),
+            "bxl" => Instruction::Bxl(literal(None)?),
+            "bst" => Instruction::Bst(combo()?),
+            "jnz" => Instruction::Jnz(literal(None)?),
+            "bxc" => Instruction::Bxc(literal(Some(0))?),
+            "out" => Instruction::Out(combo()?),
+            "bdv" => Instruction::Bdv(combo()?),
+            "cdv" => Instruction::Cdv(combo()?),
+            _ => {
+                return Err(Error::UnknownMnemonic {
+                    line: line_number,
+                    mnemonic: mnemonic.to_string(),
+                })
+            }
+        };
+        if let Some(extra) = tokens.next() {
+            return Err(invalid(extra));
+        }
+        program.extend(instruction.encode());
+    }
+    Ok(program)
+}
+
+/// Disassembles a program with one instruction per line, prefixed by its address.
+///
+/// Words that do not decode to an instruction are shown as raw values.
+pub fn disassemble(program: &[u8]) -> String {
+    program
+        .chunks(2)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let address = index * 2;
+            match chunk {
+                &[opcode, operand] => match Instruction::decode(opcode, operand) {
+                    Ok(instruction) => {
+                        format!("{address:03}: {instruction:<7}// {}", instruction.comment())
+                    }
+                    Err(error) => format!("{address:03}: ??? {opcode},{operand} // {error}"),
+                },
+                _ => format!("{address:03}: ??? {}", chunk[0]),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One executed instruction and its effect on the registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub instruction: Instruction,
+    pub before: Registers,
+    pub after: Registers,
+    pub output: Option<u8>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:03}: {:<6} {}",
+            self.before.ip, self.instruction, self.after
+        )?;
+        if let Some(output) = self.output {
+            write!(f, " -> {output}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    Halted,
+    Breakpoint(usize),
+    StepLimit,
+}
+
+/// The 3-bit computer, with support for breakpoints and a cap on the number of steps.
+#[derive(Debug, Clone)]
+pub struct Machine {
+    pub registers: Registers,
+    program: Vec<u8>,
+    output: Vec<u8>,
+    steps: usize,
+    step_limit: Option<usize>,
+    breakpoints: BTreeSet<usize>,
+}
+
+impl Machine {
+    pub fn new(program: Vec<u8>, registers: Registers) -> Self {
+        Self {
+            registers,
+            program,
+            output: vec![],
+            steps: 0,
+            step_limit: None,
+            breakpoints: BTreeSet::new(),
+        }
+    }
+
+    /// Parses the puzzle input: the three registers, a blank line, and the program.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let mut lines = input.lines();
+        let mut register = |name: char| -> Result<usize, Error> {
+            Ok(lines
+                .next()
+                .ok_or(Error::MissingRegisterLine(name))?
+                .strip_prefix(&format!("Register {name}: "))
+                .ok_or(Error::InvalidRegisterPrefix(name))?
+                .parse()?)
+        };
+        let a = register('A')?;
+        let b = register('B')?;
+        let c = register('C')?;
+        lines.next().ok_or(Error::MissingBlankLine)?;
+        let program = lines
+            .next()
+            .ok_or(Error::MissingProgramLine)?
+            .strip_prefix("Program: ")
+            .ok_or(Error::InvalidProgramPrefix)?
+            .split(',')
+            .map(|s| match s.trim().parse()? {
+                value @ 0..=7 => Ok(value),
+                value => Err(Error::InvalidProgramValue(value)),
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self::new(program, Registers { a, b, c, ip: 0 }))
+    }
+
+    pub fn program(&self) -> &[u8] {
+        &self.program
+    }
+
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /// The number of instructions executed since the machine was created or reset.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Restarts the program with new register values, keeping breakpoints and the step limit.
+    pub fn reset(&mut self, registers: Registers) {
+        self.registers = registers;
+        self.output.clear();
+        self.steps = 0;
+    }
+
+    pub fn set_step_limit(&mut self, limit: Option<usize>) {
+        self.step_limit = limit;
+    }
+
+    pub fn step_limit(&self) -> Option<usize> {
+        self.step_limit
+    }
+
+    pub fn add_breakpoint(&mut self, ip: usize) -> bool {
+        self.breakpoints.insert(ip)
+    }
+
+    pub fn remove_breakpoint(&mut self, ip: usize) -> bool {
+        self.breakpoints.remove(&ip)
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.registers.ip + 1 >= self.program.len()
+    }
+
+    /// The instruction at the instruction pointer, or `None` if the program has halted.
+    pub fn current_instruction(&self) -> Option<Result<Instruction, Error>> {
+        let ip = self.registers.ip;
+        match self.program.get(ip..ip + 2) {
+            Some(&[opcode, operand]) => Some(Instruction::decode(opcode, operand)),
+            _ => None,
+        }
+    }
+
+    /// Executes one instruction, returning `None` if the program has halted.
+    pub fn step(&mut self) -> Result<Option<Step>, Error> {
+        let Some(instruction) = self.current_instruction().transpose()? else {
+            return Ok(None);
+        };
+        let before = self.registers;
+        let output = instruction.apply(&mut self.registers);
+        self.output.extend(output);
+        self.steps += 1;
+        Ok(Some(Step {
+            instruction,
+            before,
+            after: self.registers,
+            output,
+        }))
+    }
+
+    /// Runs until the program halts, reaches a breakpoint or hits the step limit.
+    ///
+    /// A breakpoint at the starting instruction pointer is ignored, so that a
+    /// stopped machine can be resumed.
+    pub fn run(&mut self) -> Result<StopReason, Error> {
+        self.run_traced(|_| ())
+    }
+
+    /// Like [`Machine::run`], calling `trace` with every executed step.
+    pub fn run_traced(&mut self, mut trace: impl FnMut(&Step)) -> Result<StopReason, Error> {
+        let mut first = true;
+        loop {
+            if self.is_halted() {
+                return Ok(StopReason::Halted);
+            }
+            if !first && self.breakpoints.contains(&self.registers.ip) {
+                return Ok(StopReason::Breakpoint(self.registers.ip));
+            }
+            if self.step_limit.is_some_and(|limit| self.steps >= limit) {
+                return Ok(StopReason::StepLimit);
+            }
+            if let Some(step) = self.step()? {
+                trace(&step);
+            }
+            first = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn run_example() {
+        let mut machine = Machine::parse(EXAMPLE).unwrap();
+        assert_eq!(machine.run(), Ok(StopReason::Halted));
+        assert_eq!(machine.output(), [4, 6, 3, 5, 6, 3, 5, 2, 1, 0]);
+    }
+
+    #[test]
+    fn small_examples() {
+        let run = |program: &str, registers: Registers| {
+            let mut machine = Machine::new(assemble(program).unwrap(), registers);
+            machine.run().unwrap();
+            (machine.registers, machine.output().to_vec())
+        };
+
+        let (registers, _) = run(
+            "bst C",
+            Registers {
+                c: 9,
+                ..Default::default()
+            },
+        );
+        assert_eq!(registers.b, 1);
+
+        let (_, output) = run(
+            "out 0\nout 1\nout A",
+            Registers {
+                a: 10,
+                ..Default::default()
+            },
+        );
+        assert_eq!(output, [0, 1, 2]);
+
+        let (registers, output) = run(
+            "adv 1\nout A\njnz 0",
+            Registers {
+                a: 2024,
+                ..Default::default()
+            },
+        );
+        assert_eq!(output, [4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+        assert_eq!(registers.a, 0);
+
+        let (registers, _) = run(
+            "bxl 7",
+            Registers {
+                b: 29,
+                ..Default::default()
+            },
+        );
+        assert_eq!(registers.b, 26);
+
+        let (registers, _) = run(
+            "bxc 0",
+            Registers {
+                b: 2024,
+                c: 43690,
+                ..Default::default()
+            },
+        );
+        assert_eq!(registers.b, 44354);
+    }
+
+    #[test]
+    fn assemble_round_trip() {
+        let program = [2, 4, 1, 5, 7, 5, 4, 3, 1, 6, 0, 3, 5, 5, 3, 0];
+        let source = disassemble(&program);
+        assert!(source.starts_with("000: bst A  // Write the value of A modulo 8"));
+        assert_eq!(assemble(&source).unwrap(), program);
+    }
+
+    #[test]
+    fn assemble_errors() {
+        assert_eq!(
+            assemble("adv 1\nfoo 2"),
+            Err(Error::UnknownMnemonic {
+                line: 2,
+                mnemonic: "foo".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("adv 7"),
+            Err(Error::InvalidAssemblyOperand {
+                line: 1,
+                operand: "7".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("out"),
+            Err(Error::MissingAssemblyOperand { line: 1 })
+        );
+    }
+
+    #[test]
+    fn breakpoints_and_step_limit() {
+        let mut machine = Machine::parse(EXAMPLE).unwrap();
+        machine.add_breakpoint(2);
+        assert_eq!(machine.run(), Ok(StopReason::Breakpoint(2)));
+        assert_eq!(machine.registers.a, 729 >> 1);
+        assert_eq!(machine.run(), Ok(StopReason::Breakpoint(2)));
+        assert_eq!(machine.output(), [4]);
+
+        machine.remove_breakpoint(2);
+        machine.set_step_limit(Some(10));
+        assert_eq!(machine.run(), Ok(StopReason::StepLimit));
+        assert_eq!(machine.steps(), 10);
+    }
+
+    #[test]
+    fn trace() {
+        let mut machine = Machine::parse(EXAMPLE).unwrap();
+        let mut steps = vec![];
+        machine.set_step_limit(Some(3));
+        machine.run_traced(|step| steps.push(*step)).unwrap();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(
+            steps[0].instruction,
+            Instruction::Adv(ComboOperand::Literal(1))
+        );
+        assert_eq!(steps[1].output, Some(4));
+        assert_eq!(steps[2].after.ip, 0);
+        assert_eq!(
+            steps[1].to_string(),
+            "002: out A  A=364 B=0 C=0 IP=004 -> 4"
+        );
+    }
+
+    #[test]
+    fn reserved_operand() {
+        let mut machine = Machine::new(vec![5, 7], Registers::default());
+        assert_eq!(machine.step(), Err(Error::ReservedOperandValue));
+    }
+}
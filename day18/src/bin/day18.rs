@@ -0,0 +1,27 @@
+use day18::timeline::Timeline;
+use std::{error::Error, fs};
+
+const USAGE: &str = "Usage: day18 lengths <file> [size]";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(2);
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let (path, size) = match args.as_slice() {
+        [command, path] if command == "lengths" => (path, 71),
+        [command, path, size] if command == "lengths" => (path, size.parse::<usize>()?),
+        _ => usage(),
+    };
+    let timeline = Timeline::parse(&fs::read_to_string(path)?, size, size)?;
+    println!("bytes,steps");
+    for (bytes, length) in timeline.path_lengths().into_iter().enumerate() {
+        match length {
+            Some(steps) => println!("{bytes},{steps}"),
+            None => println!("{bytes},"),
+        }
+    }
+    Ok(())
+}
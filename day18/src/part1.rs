@@ -1,5 +1,8 @@
+#[cfg(feature = "grid-search")]
+use aoc_grid::search;
+use aoc_grid::{Grid, Point};
+#[cfg(not(feature = "grid-search"))]
 use pathfinding::prelude::dijkstra;
-use std::collections::HashSet;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -12,46 +15,78 @@ pub enum Error {
     NoPathToEndFound,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    pub width: usize,
+    pub height: usize,
+    /// Number of bytes that have fallen.
+    pub bytes: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            width: 71,
+            height: 71,
+            bytes: 1024,
+        }
+    }
+}
+
 pub fn solve(input: &str) -> Result<usize, Error> {
-    count_steps(input, 71, 71, 1024)
+    solve_with(input, Params::default())
 }
 
-const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+pub fn solve_with(input: &str, params: Params) -> Result<usize, Error> {
+    let Params {
+        width,
+        height,
+        bytes,
+    } = params;
+    let mut memory = Grid::new(width, height, false);
+    for line in input.lines().take(bytes) {
+        if let Some(corrupted) = memory.get_mut(parse_byte(line)?) {
+            *corrupted = true;
+        }
+    }
+    let start = Point::ZERO;
+    let end = Point::new(height as i32 - 1, width as i32 - 1);
 
-fn count_steps(input: &str, width: usize, height: usize, bytes: usize) -> Result<usize, Error> {
-    let corrupted = input
-        .lines()
-        .take(bytes)
-        .map(|line| {
-            let (x, y) = line.split_once(',').ok_or(Error::MissingDelimiter)?;
-            Ok((x.parse::<usize>()?, y.parse::<usize>()?))
-        })
-        .collect::<Result<HashSet<_>, Error>>()?;
-    let start = (0_usize, 0_usize);
-    let end = (width - 1, height - 1);
+    shortest_path(&memory, start, end).ok_or(Error::NoPathToEndFound)
+}
 
+#[cfg(not(feature = "grid-search"))]
+fn shortest_path(memory: &Grid<bool>, start: Point, end: Point) -> Option<usize> {
     dijkstra(
         &start,
-        |&(x, y)| {
-            DIRECTIONS
-                .into_iter()
-                .map(move |(dx, dy)| (x, y, dx, dy))
-                .filter_map(|(x, y, dx, dy)| {
-                    let x = x.checked_add_signed(dx)?;
-                    let y = y.checked_add_signed(dy)?;
-                    if x < width && y < height && !corrupted.contains(&(x, y)) {
-                        Some(((x, y), 1))
-                    } else {
-                        None
-                    }
-                })
+        |&position| {
+            memory
+                .neighbours(position)
+                .filter(|(_, &corrupted)| !corrupted)
+                .map(|(next, _)| (next, 1))
         },
         |&position| position == end,
     )
-    .ok_or(Error::NoPathToEndFound)
     .map(|(_, cost)| cost)
 }
 
+#[cfg(feature = "grid-search")]
+fn shortest_path(memory: &Grid<bool>, start: Point, end: Point) -> Option<usize> {
+    let neighbours = |index| {
+        memory
+            .neighbours(memory.point_of(index))
+            .filter(|(_, &corrupted)| !corrupted)
+            .filter_map(|(next, _)| Some((memory.index_of(next)?, 1)))
+    };
+    let (start, end) = (memory.index_of(start)?, memory.index_of(end)?);
+    search::bidirectional(memory.len(), start, end, neighbours, neighbours).map(|(_, cost)| cost)
+}
+
+fn parse_byte(line: &str) -> Result<Point, Error> {
+    let (x, y) = line.split_once(',').ok_or(Error::MissingDelimiter)?;
+    Ok(Point::new(y.parse()?, x.parse()?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -60,7 +95,12 @@ mod tests {
 
     #[test]
     fn example() {
-        let result = count_steps(EXAMPLE, 7, 7, 12).unwrap();
+        let params = Params {
+            width: 7,
+            height: 7,
+            bytes: 12,
+        };
+        let result = solve_with(EXAMPLE, params).unwrap();
         assert_eq!(result, 22);
     }
 
@@ -0,0 +1,250 @@
+use aoc_grid::{Grid, Point};
+use std::collections::VecDeque;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Missing delimiter")]
+    MissingDelimiter,
+    #[error("Failed to parse number: {0}")]
+    FailedToParseNumber(#[from] std::num::ParseIntError),
+}
+
+/// When each cell of memory stops being reachable from the top left corner as
+/// bytes fall, in the order they are listed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    bytes: Vec<Point>,
+    unreachable: Grid<Option<usize>>,
+}
+
+impl Timeline {
+    /// Builds the timeline by running time backwards: starting with every byte
+    /// fallen, bytes are lifted again from last to first, and each lift floods
+    /// any area it reconnects to the start. Every cell is visited once.
+    pub fn new(width: usize, height: usize, bytes: Vec<Point>) -> Self {
+        let mut fallen = Grid::new(width, height, None);
+        for (index, &byte) in bytes.iter().enumerate() {
+            if let Some(cell @ None) = fallen.get_mut(byte) {
+                *cell = Some(index);
+            }
+        }
+
+        let mut unreachable = Grid::new(width, height, None);
+        let mut reached = Grid::new(width, height, false);
+        let mut stack = vec![];
+        let start = Point::ZERO;
+        if fallen.get(start) == Some(&None) {
+            stack.push(start);
+        }
+        let mut time = bytes.len();
+        loop {
+            while let Some(position) = stack.pop() {
+                if std::mem::replace(&mut reached[position], true) {
+                    continue;
+                }
+                if time < bytes.len() {
+                    unreachable[position] = Some(time + 1);
+                }
+                stack.extend(
+                    fallen
+                        .neighbours(position)
+                        .filter(|&(next, cell)| {
+                            cell.is_none_or(|fell| fell >= time) && !reached[next]
+                        })
+                        .map(|(next, _)| next),
+                );
+            }
+            if time == 0 {
+                break;
+            }
+            time -= 1;
+            let byte = bytes[time];
+            if fallen.get(byte) == Some(&Some(time))
+                && (byte == start || fallen.neighbours(byte).any(|(next, _)| reached[next]))
+            {
+                stack.push(byte);
+            }
+        }
+        Self { bytes, unreachable }
+    }
+
+    /// Parses one `x,y` byte position per line.
+    pub fn parse(input: &str, width: usize, height: usize) -> Result<Self, Error> {
+        let bytes = input
+            .lines()
+            .map(|line| {
+                let (x, y) = line.split_once(',').ok_or(Error::MissingDelimiter)?;
+                Ok(Point::new(y.parse()?, x.parse()?))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(Self::new(width, height, bytes))
+    }
+
+    pub fn bytes(&self) -> &[Point] {
+        &self.bytes
+    }
+
+    /// The number of fallen bytes after which each cell can no longer be
+    /// reached, or `None` if it can still be reached once all have fallen.
+    pub fn grid(&self) -> &Grid<Option<usize>> {
+        &self.unreachable
+    }
+
+    pub fn unreachable_after(&self, position: Point) -> Option<usize> {
+        self.unreachable.get(position).copied().flatten()
+    }
+
+    /// The byte that cuts the bottom right corner off from the start.
+    pub fn first_blocker(&self) -> Option<Point> {
+        self.unreachable_after(self.end())
+            .map(|fallen| self.bytes[fallen - 1])
+    }
+
+    /// The shortest path length from start to end after each number of fallen
+    /// bytes, from none to all of them, or `None` once the end is cut off. The
+    /// search is only repeated when a byte lands on the current path.
+    pub fn path_lengths(&self) -> Vec<Option<usize>> {
+        let mut fallen = Grid::new(
+            self.unreachable.width(),
+            self.unreachable.height(),
+            usize::MAX,
+        );
+        for (index, &byte) in self.bytes.iter().enumerate().rev() {
+            if let Some(cell) = fallen.get_mut(byte) {
+                *cell = index;
+            }
+        }
+
+        let mut lengths = vec![None; self.bytes.len() + 1];
+        let mut path = None;
+        for (time, length) in lengths.iter_mut().enumerate() {
+            let hit = |path: &Grid<bool>| time > 0 && path.get(self.bytes[time - 1]) == Some(&true);
+            if path.as_ref().is_none_or(|(path, _)| hit(path)) {
+                path = self.shortest_path(&fallen, time);
+            }
+            match &path {
+                Some((_, steps)) => *length = Some(*steps),
+                None => break,
+            }
+        }
+        lengths
+    }
+
+    /// Breadth-first search avoiding bytes that fell before `time`, returning
+    /// the cells on one shortest path and its length.
+    fn shortest_path(&self, fallen: &Grid<usize>, time: usize) -> Option<(Grid<bool>, usize)> {
+        let (start, end) = (Point::ZERO, self.end());
+        let mut parents = Grid::new(fallen.width(), fallen.height(), None);
+        let mut queue = VecDeque::new();
+        if *fallen.get(start)? >= time {
+            parents[start] = Some(start);
+            queue.push_back(start);
+        }
+        while let Some(position) = queue.pop_front() {
+            if position == end {
+                break;
+            }
+            for (next, &fell) in fallen.neighbours(position) {
+                if fell >= time && parents[next].is_none() {
+                    parents[next] = Some(position);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut path = Grid::new(fallen.width(), fallen.height(), false);
+        let mut position = end;
+        let mut steps = 0;
+        path[end] = parents.get(end).copied().flatten().is_some();
+        while position != start {
+            position = parents.get(position).copied().flatten()?;
+            path[position] = true;
+            steps += 1;
+        }
+        Some((path, steps))
+    }
+
+    fn end(&self) -> Point {
+        Point::new(
+            self.unreachable.height() as i32 - 1,
+            self.unreachable.width() as i32 - 1,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    fn reachable(width: usize, height: usize, bytes: &[Point]) -> Grid<bool> {
+        let mut memory = Grid::new(width, height, false);
+        for &byte in bytes {
+            if let Some(cell) = memory.get_mut(byte) {
+                *cell = true;
+            }
+        }
+        let mut reachable = Grid::new(width, height, false);
+        let mut stack = vec![Point::ZERO];
+        while let Some(position) = stack.pop() {
+            if memory[position] || std::mem::replace(&mut reachable[position], true) {
+                continue;
+            }
+            stack.extend(memory.neighbours(position).map(|(next, _)| next));
+        }
+        reachable
+    }
+
+    #[test]
+    fn example() {
+        let timeline = Timeline::parse(EXAMPLE, 7, 7).unwrap();
+        assert_eq!(timeline.first_blocker(), Some(Point::new(1, 6)));
+        assert_eq!(timeline.unreachable_after(Point::new(6, 6)), Some(21));
+        assert_eq!(timeline.unreachable_after(Point::ZERO), None);
+
+        for fallen in 0..=timeline.bytes().len() {
+            let reachable = reachable(7, 7, &timeline.bytes()[..fallen]);
+            for (position, &after) in timeline.grid().iter() {
+                assert_eq!(
+                    reachable[position],
+                    after.is_none_or(|after| fallen < after),
+                    "{position:?} after {fallen} bytes"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn path_lengths() {
+        let timeline = Timeline::parse(EXAMPLE, 7, 7).unwrap();
+        let lengths = timeline.path_lengths();
+        assert_eq!(lengths.len(), timeline.bytes().len() + 1);
+        assert_eq!(lengths[12], Some(22));
+        for (bytes, &length) in lengths.iter().enumerate() {
+            let params = part1::Params {
+                width: 7,
+                height: 7,
+                bytes,
+            };
+            assert_eq!(length, part1::solve_with(EXAMPLE, params).ok());
+        }
+        assert!(lengths.windows(2).all(|pair| match pair {
+            [Some(before), Some(after)] => before <= after,
+            [_, after] => after.is_none(),
+            _ => unreachable!(),
+        }));
+    }
+
+    #[test]
+    fn start_corrupted() {
+        let bytes = vec![Point::new(1, 1), Point::ZERO, Point::new(5, 5)];
+        let timeline = Timeline::new(3, 3, bytes);
+        assert_eq!(timeline.unreachable_after(Point::ZERO), Some(2));
+        assert_eq!(timeline.unreachable_after(Point::new(1, 1)), Some(1));
+        assert_eq!(timeline.first_blocker(), Some(Point::ZERO));
+        assert_eq!(timeline.path_lengths(), [Some(4), Some(4), None, None]);
+    }
+}
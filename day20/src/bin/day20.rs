@@ -0,0 +1,33 @@
+use day20::racetrack::Racetrack;
+use std::{error::Error, fs};
+
+const USAGE: &str = "Usage: day20 histogram <file> [max_cheat] [min_saving]";
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(2);
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let (path, max_cheat, min_saving) = match args.as_slice() {
+        [command, path] if command == "histogram" => (path, 2, 1),
+        [command, path, max_cheat] if command == "histogram" => (path, max_cheat.parse()?, 1),
+        [command, path, max_cheat, min_saving] if command == "histogram" => {
+            (path, max_cheat.parse()?, min_saving.parse()?)
+        }
+        _ => usage(),
+    };
+    let racetrack = Racetrack::parse(&fs::read_to_string(path)?)?;
+    match racetrack.shortest_routes() {
+        1 => {}
+        routes => eprintln!(
+            "Note: {routes} shortest routes of {} picoseconds",
+            racetrack.best_time()
+        ),
+    }
+    let histogram = racetrack.histogram(max_cheat).at_least(min_saving);
+    print!("{histogram}");
+    println!("{} cheats in total", histogram.total());
+    Ok(())
+}
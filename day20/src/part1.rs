@@ -1,119 +1,31 @@
-use pathfinding::prelude::dijkstra;
-use std::collections::HashMap;
+use crate::racetrack::{self, Racetrack};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Start or end not found")]
-    StartOrEndNotFound,
-    #[error("No path found")]
-    NoPathFound,
+    #[error("Invalid racetrack: {0}")]
+    InvalidRacetrack(#[from] racetrack::Error),
 }
 
-pub fn solve(input: &str) -> Result<usize, Error> {
-    Ok(shortcuts(input)?
-        .into_iter()
-        .filter(|&(_, t)| t >= 100)
-        .count())
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    /// Minimum number of picoseconds a cheat must save to be counted.
+    pub min_saving: usize,
 }
 
-const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-
-type Position = (usize, usize);
-type ShortcutMap = HashMap<(Position, Position), usize>;
-
-fn shortcuts(input: &str) -> Result<ShortcutMap, Error> {
-    let map = input
-        .lines()
-        .map(|line| line.as_bytes())
-        .collect::<Vec<_>>();
-    let (start, end) = find_start_and_end(&map).ok_or(Error::StartOrEndNotFound)?;
-    let (route, _length) = dijkstra(
-        &start,
-        |&(x, y)| successors(x, y, &map),
-        |position| *position == end,
-    )
-    .ok_or(Error::NoPathFound)?;
-
-    let route_map = route
-        .iter()
-        .enumerate()
-        .map(|(i, &(x, y))| ((x, y), i))
-        .collect::<HashMap<_, _>>();
-
-    Ok(route
-        .into_iter()
-        .enumerate()
-        .flat_map(|(offset, position)| shortcuts_from_position(position, offset, &route_map, &map))
-        .collect())
-}
-
-fn find_start_and_end(map: &[&[u8]]) -> Option<(Position, Position)> {
-    let mut start = None;
-    let mut end = None;
-    for (y, row) in map.iter().enumerate() {
-        if let Some(x) = row.iter().position(|&c| c == b'S') {
-            start = Some((x, y));
-        }
-        if let Some(x) = row.iter().position(|&c| c == b'E') {
-            end = Some((x, y));
-        }
+impl Default for Params {
+    fn default() -> Self {
+        Self { min_saving: 100 }
     }
-    Some((start?, end?))
 }
 
-fn successors<'a>(
-    x: usize,
-    y: usize,
-    map: &'a [&[u8]],
-) -> impl Iterator<Item = (Position, usize)> + 'a {
-    DIRECTIONS
-        .into_iter()
-        .filter_map(move |(dx, dy)| {
-            let x = x.checked_add_signed(dx)?;
-            let y = y.checked_add_signed(dy)?;
-            Some((x, y))
-        })
-        .filter_map(|(x, y)| {
-            let cell = map.get(y)?.get(x)?;
-            if *cell == b'#' {
-                None
-            } else {
-                Some(((x, y), 1))
-            }
-        })
+pub fn solve(input: &str) -> Result<usize, Error> {
+    solve_with(input, Params::default())
 }
 
-fn shortcuts_from_position<'a>(
-    position: Position,
-    offset: usize,
-    route_map: &'a HashMap<Position, usize>,
-    map: &'a [&[u8]],
-) -> impl Iterator<Item = ((Position, Position), usize)> + 'a {
-    DIRECTIONS
-        .into_iter()
-        .filter_map(move |(dx, dy)| {
-            let x = position.0.checked_add_signed(dx)?;
-            let y = position.1.checked_add_signed(dy)?;
-            let cell = map.get(y)?.get(x)?;
-            if *cell == b'#' {
-                Some((x, y))
-            } else {
-                None
-            }
-        })
-        .flat_map(move |(x, y)| {
-            DIRECTIONS.into_iter().filter_map(move |(dx, dy)| {
-                let x = x.checked_add_signed(dx)?;
-                let y = y.checked_add_signed(dy)?;
-                let next = route_map.get(&(x, y))?;
-                if *next <= offset + 2 {
-                    None
-                } else {
-                    Some(((position, (x, y)), next - offset - 2))
-                }
-            })
-        })
+pub fn solve_with(input: &str, params: Params) -> Result<usize, Error> {
+    let racetrack = Racetrack::parse(input)?;
+    Ok(racetrack.histogram(2).at_least(params.min_saving).total())
 }
 
 #[cfg(test)]
@@ -124,20 +36,25 @@ mod tests {
 
     #[test]
     fn example() {
-        let result = shortcuts(EXAMPLE).unwrap();
-        println!("{:?}", result);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 2).count(), 14);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 4).count(), 14);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 6).count(), 2);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 8).count(), 4);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 10).count(), 2);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 12).count(), 3);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 20).count(), 1);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 36).count(), 1);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 38).count(), 1);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 40).count(), 1);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 64).count(), 1);
-        assert_eq!(result.len(), 44);
+        let result = Racetrack::parse(EXAMPLE).unwrap().histogram(2);
+        assert_eq!(result.count(2), 14);
+        assert_eq!(result.count(4), 14);
+        assert_eq!(result.count(6), 2);
+        assert_eq!(result.count(8), 4);
+        assert_eq!(result.count(10), 2);
+        assert_eq!(result.count(12), 3);
+        assert_eq!(result.count(20), 1);
+        assert_eq!(result.count(36), 1);
+        assert_eq!(result.count(38), 1);
+        assert_eq!(result.count(40), 1);
+        assert_eq!(result.count(64), 1);
+        assert_eq!(result.total(), 44);
+    }
+
+    #[test]
+    fn example_min_saving() {
+        let result = solve_with(EXAMPLE, Params { min_saving: 20 }).unwrap();
+        assert_eq!(result, 5);
     }
 
     #[cfg(input_txt)]
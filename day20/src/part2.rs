@@ -1,134 +1,31 @@
-use pathfinding::prelude::dijkstra;
-use rayon::prelude::*;
-use std::collections::HashMap;
+use crate::racetrack::{self, Racetrack};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Start or end not found")]
-    StartOrEndNotFound,
-    #[error("No path found")]
-    NoPathFound,
+    #[error("Invalid racetrack: {0}")]
+    InvalidRacetrack(#[from] racetrack::Error),
 }
 
-pub fn solve(input: &str) -> Result<usize, Error> {
-    Ok(shortcuts(input)?
-        .into_iter()
-        .filter(|&(_, t)| t >= 100)
-        .count())
-}
-
-const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-
-type Position = (usize, usize);
-type ShortcutMap = HashMap<(Position, Position), usize>;
-
-fn shortcuts(input: &str) -> Result<ShortcutMap, Error> {
-    let map = input
-        .lines()
-        .map(|line| line.as_bytes())
-        .collect::<Vec<_>>();
-    let (start, end) = find_start_and_end(&map).ok_or(Error::StartOrEndNotFound)?;
-    let (route, _length) = dijkstra(
-        &start,
-        |&(x, y)| successors(x, y, &map),
-        |position| *position == end,
-    )
-    .ok_or(Error::NoPathFound)?;
-
-    let route_map = route
-        .iter()
-        .enumerate()
-        .map(|(i, &(x, y))| ((x, y), i))
-        .collect::<HashMap<_, _>>();
-
-    Ok(route
-        .into_par_iter()
-        .enumerate()
-        .flat_map(|(offset, position)| {
-            shortcuts_from_position(position, offset, &route_map, &map).par_bridge()
-        })
-        .collect())
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Params {
+    /// Minimum number of picoseconds a cheat must save to be counted.
+    pub min_saving: usize,
 }
 
-fn find_start_and_end(map: &[&[u8]]) -> Option<(Position, Position)> {
-    let mut start = None;
-    let mut end = None;
-    for (y, row) in map.iter().enumerate() {
-        if let Some(x) = row.iter().position(|&c| c == b'S') {
-            start = Some((x, y));
-        }
-        if let Some(x) = row.iter().position(|&c| c == b'E') {
-            end = Some((x, y));
-        }
+impl Default for Params {
+    fn default() -> Self {
+        Self { min_saving: 100 }
     }
-    Some((start?, end?))
 }
 
-fn successors<'a>(
-    x: usize,
-    y: usize,
-    map: &'a [&[u8]],
-) -> impl Iterator<Item = (Position, usize)> + 'a {
-    DIRECTIONS
-        .into_iter()
-        .filter_map(move |(dx, dy)| {
-            let x = x.checked_add_signed(dx)?;
-            let y = y.checked_add_signed(dy)?;
-            Some((x, y))
-        })
-        .filter_map(|(x, y)| {
-            let cell = map.get(y)?.get(x)?;
-            if *cell == b'#' {
-                None
-            } else {
-                Some(((x, y), 1))
-            }
-        })
-}
-
-fn shortcuts_from_position<'a>(
-    position: Position,
-    offset: usize,
-    route_map: &'a HashMap<Position, usize>,
-    map: &'a [&[u8]],
-) -> impl Iterator<Item = ((Position, Position), usize)> + 'a {
-    shortcut_positions(position).filter_map(move |((x, y), shortcut_length)| {
-        let cell = map.get(y)?.get(x)?;
-        if *cell == b'#' {
-            return None;
-        }
-        let next = route_map.get(&(x, y))?;
-        if *next <= offset + shortcut_length {
-            None
-        } else {
-            Some(((position, (x, y)), next - offset - shortcut_length))
-        }
-    })
+pub fn solve(input: &str) -> Result<usize, Error> {
+    solve_with(input, Params::default())
 }
 
-fn shortcut_positions(start: Position) -> impl Iterator<Item = (Position, usize)> {
-    (1..20)
-        .flat_map(move |n| {
-            (1..=20 - n).flat_map(move |m| {
-                [(n, m), (-n, m), (-n, -m), (n, -m)]
-                    .into_iter()
-                    .filter_map(move |(dx, dy)| {
-                        let x = start.0.checked_add_signed(dx)?;
-                        let y = start.1.checked_add_signed(dy)?;
-                        Some(((x, y), (n + m) as usize))
-                    })
-            })
-        })
-        .chain((1..=20).flat_map(move |n| {
-            [(n, 0), (0, n), (-n, 0), (0, -n)]
-                .into_iter()
-                .filter_map(move |(dx, dy)| {
-                    let x = start.0.checked_add_signed(dx)?;
-                    let y = start.1.checked_add_signed(dy)?;
-                    Some(((x, y), n as usize))
-                })
-        }))
+pub fn solve_with(input: &str, params: Params) -> Result<usize, Error> {
+    let racetrack = Racetrack::parse(input)?;
+    Ok(racetrack.histogram(20).at_least(params.min_saving).total())
 }
 
 #[cfg(test)]
@@ -139,22 +36,27 @@ mod tests {
 
     #[test]
     fn example() {
-        let result = shortcuts(EXAMPLE).unwrap();
-        println!("{:?}", result);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 50).count(), 32);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 52).count(), 31);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 54).count(), 29);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 56).count(), 39);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 58).count(), 25);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 60).count(), 23);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 62).count(), 20);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 64).count(), 19);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 66).count(), 12);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 68).count(), 14);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 70).count(), 12);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 72).count(), 22);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 74).count(), 4);
-        assert_eq!(result.iter().filter(|&(_, &t)| t == 76).count(), 3);
+        let result = Racetrack::parse(EXAMPLE).unwrap().histogram(20);
+        assert_eq!(result.count(50), 32);
+        assert_eq!(result.count(52), 31);
+        assert_eq!(result.count(54), 29);
+        assert_eq!(result.count(56), 39);
+        assert_eq!(result.count(58), 25);
+        assert_eq!(result.count(60), 23);
+        assert_eq!(result.count(62), 20);
+        assert_eq!(result.count(64), 19);
+        assert_eq!(result.count(66), 12);
+        assert_eq!(result.count(68), 14);
+        assert_eq!(result.count(70), 12);
+        assert_eq!(result.count(72), 22);
+        assert_eq!(result.count(74), 4);
+        assert_eq!(result.count(76), 3);
+    }
+
+    #[test]
+    fn example_min_saving() {
+        let result = solve_with(EXAMPLE, Params { min_saving: 70 }).unwrap();
+        assert_eq!(result, 41);
     }
 
     #[cfg(input_txt)]
@@ -0,0 +1,313 @@
+#[cfg(feature = "grid-search")]
+use aoc_grid::search;
+use aoc_grid::{Grid, Point};
+#[cfg(not(feature = "grid-search"))]
+use pathfinding::prelude::dijkstra_all;
+use rayon::prelude::*;
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid map: {0}")]
+    InvalidMap(#[from] aoc_grid::Error),
+    #[error("Start or end not found")]
+    StartOrEndNotFound,
+    #[error("No path found")]
+    NoPathFound,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Racetrack {
+    map: Grid<u8>,
+    /// Picoseconds from the start to each tile.
+    from_start: Grid<Option<usize>>,
+    /// Picoseconds from each tile to the end.
+    to_end: Grid<Option<usize>>,
+    route: Vec<Point>,
+}
+
+impl Racetrack {
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let map = Grid::parse(input)?;
+        let start = map.find(b'S').ok_or(Error::StartOrEndNotFound)?;
+        let end = map.find(b'E').ok_or(Error::StartOrEndNotFound)?;
+        let from_start = distances(&map, start);
+        let to_end = distances(&map, end);
+
+        let mut route = vec![start];
+        let mut position = start;
+        let mut remaining = to_end[start].ok_or(Error::NoPathFound)?;
+        while remaining > 0 {
+            remaining -= 1;
+            (position, _) = to_end
+                .neighbours(position)
+                .find(|&(_, &distance)| distance == Some(remaining))
+                .ok_or(Error::NoPathFound)?;
+            route.push(position);
+        }
+
+        Ok(Self {
+            map,
+            from_start,
+            to_end,
+            route,
+        })
+    }
+
+    pub fn map(&self) -> &Grid<u8> {
+        &self.map
+    }
+
+    /// One of the shortest routes from start to end.
+    pub fn route(&self) -> &[Point] {
+        &self.route
+    }
+
+    /// Picoseconds to race from start to end without cheating.
+    pub fn best_time(&self) -> usize {
+        self.route.len() - 1
+    }
+
+    /// The number of different shortest routes, saturating at `usize::MAX`.
+    /// Cheats are counted correctly either way, but on a map with more than one
+    /// the route is not the whole story.
+    pub fn shortest_routes(&self) -> usize {
+        let best = self.best_time();
+        let mut layers = vec![vec![]; best + 1];
+        for (position, &distance) in self.from_start.iter() {
+            if let (Some(from_start), Some(to_end)) = (distance, self.to_end[position]) {
+                if from_start + to_end == best {
+                    layers[from_start].push(position);
+                }
+            }
+        }
+        let mut routes = self.map.map(|_| 0usize);
+        routes[self.route[0]] = 1;
+        for (distance, layer) in layers.iter().enumerate().skip(1) {
+            for &position in layer {
+                routes[position] = self
+                    .from_start
+                    .neighbours(position)
+                    .filter(|&(_, &previous)| previous == Some(distance - 1))
+                    .fold(0, |total, (previous, _)| {
+                        total.saturating_add(routes[previous])
+                    });
+            }
+        }
+        routes[self.route[best]]
+    }
+
+    /// Counts the cheats lasting up to `max_cheat` picoseconds by the time they
+    /// save. A cheat is identified by the tiles it starts and ends on, and only
+    /// cheats that save time are counted. Tiles off the shortest route are
+    /// included, so maps with forks and loops are handled too.
+    pub fn histogram(&self, max_cheat: usize) -> Histogram {
+        let best = self.best_time();
+        let counts = self
+            .from_start
+            .iter()
+            .filter_map(|(position, &distance)| Some((position, distance?)))
+            .par_bridge()
+            .fold(
+                || vec![0; best],
+                |mut counts, (position, from_start)| {
+                    for (next, length) in cheat_ends(position, max_cheat) {
+                        if let Some(&Some(to_end)) = self.to_end.get(next) {
+                            let time = from_start + length + to_end;
+                            if time < best {
+                                counts[best - time] += 1;
+                            }
+                        }
+                    }
+                    counts
+                },
+            )
+            .reduce(
+                || vec![0; best],
+                |mut counts, other| {
+                    counts.iter_mut().zip(other).for_each(|(a, b)| *a += b);
+                    counts
+                },
+            );
+        Histogram { counts }
+    }
+}
+
+/// Number of cheats by the picoseconds they save.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    counts: Vec<usize>,
+}
+
+impl Histogram {
+    pub fn count(&self, saving: usize) -> usize {
+        self.counts.get(saving).copied().unwrap_or(0)
+    }
+
+    /// Savings and how many cheats achieve them, smallest saving first.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(saving, &count)| (saving, count))
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Drops the cheats that save less than `min_saving` picoseconds.
+    pub fn at_least(mut self, min_saving: usize) -> Self {
+        let below = min_saving.min(self.counts.len());
+        self.counts[..below].fill(0);
+        self
+    }
+}
+
+/// Lists the cheats the way the puzzle does.
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (saving, count) in self.iter() {
+            match count {
+                1 => writeln!(f, "There is one cheat that saves {saving} picoseconds.")?,
+                _ => writeln!(
+                    f,
+                    "There are {count} cheats that save {saving} picoseconds."
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "grid-search"))]
+fn distances(map: &Grid<u8>, from: Point) -> Grid<Option<usize>> {
+    let mut distances = map.map(|_| None);
+    distances[from] = Some(0);
+    for (position, (_, distance)) in dijkstra_all(&from, |&position| successors(position, map)) {
+        distances[position] = Some(distance);
+    }
+    distances
+}
+
+#[cfg(feature = "grid-search")]
+fn distances(map: &Grid<u8>, from: Point) -> Grid<Option<usize>> {
+    let mut distances = map.map(|_| None);
+    let reached = search::dijkstra_all(map.len(), map.index_of(from), |index| {
+        successors(map.point_of(index), map)
+            .filter_map(|(next, cost)| Some((map.index_of(next)?, cost)))
+    });
+    for (index, distance) in reached.iter() {
+        distances[map.point_of(index)] = Some(distance);
+    }
+    distances
+}
+
+fn successors(position: Point, map: &Grid<u8>) -> impl Iterator<Item = (Point, usize)> + '_ {
+    map.neighbours(position)
+        .filter(|(_, &cell)| cell != b'#')
+        .map(|(next, _)| (next, 1))
+}
+
+/// Every position within `max_cheat` steps, with its distance.
+fn cheat_ends(start: Point, max_cheat: usize) -> impl Iterator<Item = (Point, usize)> {
+    let max_cheat = max_cheat as i32;
+    (-max_cheat..=max_cheat).flat_map(move |dy| {
+        let width = max_cheat - dy.abs();
+        (-width..=width).map(move |dx| (start + Point::new(dy, dx), (dy.abs() + dx.abs()) as usize))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn histogram() {
+        let racetrack = Racetrack::parse(EXAMPLE).unwrap();
+        assert_eq!(racetrack.route().len(), 85);
+        let histogram = racetrack.histogram(2);
+        assert_eq!(histogram.total(), 44);
+        assert_eq!(histogram.at_least(20).total(), 5);
+        assert_eq!(
+            racetrack.histogram(20).at_least(74).to_string(),
+            "There are 4 cheats that save 74 picoseconds.\n\
+             There are 3 cheats that save 76 picoseconds.\n"
+        );
+        assert_eq!(racetrack.histogram(0).total(), 0);
+        assert_eq!(racetrack.best_time(), 84);
+        assert_eq!(racetrack.shortest_routes(), 1);
+    }
+
+    #[test]
+    fn two_routes() {
+        let racetrack = Racetrack::parse("#######\n#.....#\n#S###E#\n#.....#\n#######").unwrap();
+        assert_eq!(racetrack.best_time(), 6);
+        assert_eq!(racetrack.shortest_routes(), 2);
+        assert_eq!(racetrack.histogram(2).total(), 0);
+        assert_eq!(racetrack.histogram(4).iter().collect::<Vec<_>>(), [(2, 1)]);
+    }
+
+    fn bfs(map: &Grid<u8>, from: Point) -> Grid<Option<usize>> {
+        let mut distances = map.map(|_| None);
+        let mut frontier = vec![from];
+        for distance in 0.. {
+            if frontier.is_empty() {
+                break;
+            }
+            for &position in &frontier {
+                distances[position] = Some(distance);
+            }
+            frontier = frontier
+                .iter()
+                .flat_map(|&position| map.neighbours(position))
+                .filter(|&(next, &tile)| tile != b'#' && distances[next].is_none())
+                .map(|(next, _)| next)
+                .collect();
+            frontier.sort();
+            frontier.dedup();
+        }
+        distances
+    }
+
+    #[test]
+    fn branching() {
+        let input = "\
+#########
+#S..#...#
+#.#.#.#.#
+#...#.#E#
+#.#...#.#
+#.......#
+#########";
+        let racetrack = Racetrack::parse(input).unwrap();
+        let map = racetrack.map();
+        let (from_start, to_end) = (bfs(map, Point::new(1, 1)), bfs(map, Point::new(3, 7)));
+        let best = to_end[Point::new(1, 1)].unwrap();
+        assert_eq!(racetrack.best_time(), best);
+        assert_eq!(racetrack.shortest_routes(), 7);
+
+        for max_cheat in [2, 3, 6] {
+            let mut expected = vec![0; best];
+            for (a, &from_start) in from_start.iter() {
+                for (b, &to_end) in to_end.iter() {
+                    let length = a.manhattan_distance(b) as usize;
+                    if let (Some(from_start), Some(to_end)) = (from_start, to_end) {
+                        let time = from_start + length + to_end;
+                        if length <= max_cheat && time < best {
+                            expected[best - time] += 1;
+                        }
+                    }
+                }
+            }
+            assert_eq!(
+                racetrack.histogram(max_cheat),
+                Histogram { counts: expected }
+            );
+        }
+    }
+}
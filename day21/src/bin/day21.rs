@@ -0,0 +1,50 @@
+use day21::keypad::Robots;
+use std::error::Error;
+
+const USAGE: &str = "\
+Usage: day21 presses <code> [robots]
+       day21 check <code> <presses> [robots]";
+
+/// Longer sequences are cut short when printed.
+const MAX_PRESSES: usize = 120;
+
+fn usage() -> ! {
+    eprintln!("{USAGE}");
+    std::process::exit(2);
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["presses", code, rest @ ..] if rest.len() <= 1 => {
+            let robots = Robots::standard(rest.first().map_or(Ok(2), |n| n.parse())?);
+            for level in (0..=robots.robots() + 1).rev() {
+                let length = robots.length(code.as_bytes(), level)?;
+                let presses = robots.presses(code.as_bytes(), level)?.take(MAX_PRESSES);
+                let text = String::from_utf8(presses.collect())?;
+                let more = if length > MAX_PRESSES { "..." } else { "" };
+                println!("Level {level}, {length} presses: {text}{more}");
+            }
+        }
+        ["check", code, presses, rest @ ..] if rest.len() <= 1 => {
+            let robots = Robots::standard(rest.first().map_or(Ok(2), |n| n.parse())?);
+            let typed = robots.simulate(0, presses.bytes())?;
+            let typed = String::from_utf8(typed)?;
+            let optimal = robots.length(code.as_bytes(), 0)?;
+            println!(
+                "Typed {typed:?}, {} presses, fewest {optimal}",
+                presses.len()
+            );
+            if typed != *code {
+                std::process::exit(1);
+            }
+        }
+        _ => usage(),
+    }
+    Ok(())
+}
@@ -0,0 +1,581 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    str::FromStr,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Key {0:?} appears more than once")]
+    DuplicateKey(char),
+    #[error("Keypad has no {0:?} key")]
+    MissingKey(char),
+    #[error("Key {0:?} is not on the keypad")]
+    UnknownKey(char),
+    #[error("No route from {0:?} to {1:?}")]
+    Unreachable(char, char),
+    #[error("No keypad at level {0}")]
+    NoLevel(usize),
+    #[error("Arm over a gap on the keypad at level {0}")]
+    ArmOverGap(usize),
+}
+
+/// The key every arm starts on, which presses the key the next arm points at.
+const ACTIVATE: u8 = b'A';
+
+/// Directional keys and the row and column offsets they move an arm by.
+const DIRECTIONS: [(u8, (i32, i32)); 4] = [
+    (b'^', (-1, 0)),
+    (b'>', (0, 1)),
+    (b'v', (1, 0)),
+    (b'<', (0, -1)),
+];
+
+/// A grid of keys. Spaces are gaps that no arm may point at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keypad {
+    keys: HashMap<u8, (i32, i32)>,
+    positions: HashMap<(i32, i32), u8>,
+}
+
+impl Keypad {
+    pub const NUMERIC: &str = "789\n456\n123\n 0A";
+    pub const DIRECTIONAL: &str = " ^A\n<v>";
+
+    pub fn numeric() -> Self {
+        Self::NUMERIC.parse().expect("valid numeric keypad")
+    }
+
+    pub fn directional() -> Self {
+        Self::DIRECTIONAL.parse().expect("valid directional keypad")
+    }
+
+    /// Keys in reading order.
+    pub fn keys(&self) -> impl Iterator<Item = u8> {
+        let mut keys = self.keys.iter().collect::<Vec<_>>();
+        keys.sort_by_key(|&(_, position)| position);
+        keys.into_iter()
+            .map(|(&key, _)| key)
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Row and column of a key.
+    pub fn position(&self, key: u8) -> Option<(i32, i32)> {
+        self.keys.get(&key).copied()
+    }
+
+    /// Every shortest sequence of directional presses that moves an arm from
+    /// one key to another without pointing at a gap, each followed by `A`.
+    pub fn routes(&self, from: u8, to: u8) -> Vec<Vec<u8>> {
+        let (Some(start), Some(end)) = (self.position(from), self.position(to)) else {
+            return vec![];
+        };
+
+        // Distances back from the target, so that routes can be followed downhill.
+        let mut distances = HashMap::from([(end, 0)]);
+        let mut queue = VecDeque::from([end]);
+        while let Some(position) = queue.pop_front() {
+            let distance = distances[&position];
+            for next in self.neighbours(position) {
+                distances.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    distance + 1
+                });
+            }
+        }
+        if !distances.contains_key(&start) {
+            return vec![];
+        }
+
+        let mut routes = vec![];
+        let mut stack = vec![(start, vec![])];
+        while let Some((position, mut route)) = stack.pop() {
+            if position == end {
+                route.push(ACTIVATE);
+                routes.push(route);
+                continue;
+            }
+            for (key, (dr, dc)) in DIRECTIONS {
+                let next = (position.0 + dr, position.1 + dc);
+                if distances.get(&next) == Some(&(distances[&position] - 1)) {
+                    let mut route = route.clone();
+                    route.push(key);
+                    stack.push((next, route));
+                }
+            }
+        }
+        routes
+    }
+
+    fn neighbours(&self, (row, column): (i32, i32)) -> impl Iterator<Item = (i32, i32)> + '_ {
+        DIRECTIONS
+            .into_iter()
+            .map(move |(_, (dr, dc))| (row + dr, column + dc))
+            .filter(|next| self.positions.contains_key(next))
+    }
+}
+
+impl FromStr for Keypad {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut keys = HashMap::new();
+        let mut positions = HashMap::new();
+        for (row, line) in s.lines().enumerate() {
+            for (column, key) in line.bytes().enumerate() {
+                if key == b' ' {
+                    continue;
+                }
+                let position = (row as i32, column as i32);
+                if keys.insert(key, position).is_some() {
+                    return Err(Error::DuplicateKey(key as char));
+                }
+                positions.insert(position, key);
+            }
+        }
+        if !keys.contains_key(&ACTIVATE) {
+            return Err(Error::MissingKey(ACTIVATE as char));
+        }
+        Ok(Self { keys, positions })
+    }
+}
+
+/// Best moves and their lengths in human presses between every pair of keys on
+/// one keypad in the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Table {
+    moves: HashMap<(u8, u8), Vec<u8>>,
+    lengths: HashMap<(u8, u8), usize>,
+}
+
+impl Table {
+    /// The keypad the human presses directly.
+    fn direct(keypad: &Keypad) -> Self {
+        let mut moves = HashMap::new();
+        let mut lengths = HashMap::new();
+        for from in keypad.keys() {
+            for to in keypad.keys() {
+                moves.insert((from, to), vec![to]);
+                lengths.insert((from, to), 1);
+            }
+        }
+        Self { moves, lengths }
+    }
+
+    /// A keypad whose robot is driven from the keypad `below`, picking whichever
+    /// of its shortest routes takes the fewest presses there.
+    fn new(keypad: &Keypad, below: &Table) -> Result<Self, Error> {
+        let mut moves = HashMap::new();
+        let mut lengths = HashMap::new();
+        for from in keypad.keys() {
+            for to in keypad.keys() {
+                let (length, route) = keypad
+                    .routes(from, to)
+                    .into_iter()
+                    .map(|route| (below.sequence_length(&route), route))
+                    .min_by_key(|&(length, _)| length)
+                    .ok_or(Error::Unreachable(from as char, to as char))?;
+                moves.insert((from, to), route);
+                lengths.insert((from, to), length);
+            }
+        }
+        Ok(Self { moves, lengths })
+    }
+
+    /// Human presses needed to type `sequence` on this keypad, starting at `A`.
+    fn sequence_length(&self, sequence: &[u8]) -> usize {
+        sequence
+            .iter()
+            .fold((ACTIVATE, 0usize), |(previous, total), &key| {
+                (key, total.saturating_add(self.lengths[&(previous, key)]))
+            })
+            .1
+    }
+}
+
+/// A chain of robots typing on a door keypad, each driven through a directional
+/// keypad by the next, with the human pressing the last one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Robots {
+    door: Keypad,
+    controls: Keypad,
+    /// The human's keypad first and the door keypad last.
+    tables: Vec<Table>,
+}
+
+impl Robots {
+    /// `robots` is the number of robots at directional keypads between the
+    /// human and the robot at the door.
+    pub fn new(door: Keypad, controls: Keypad, robots: usize) -> Result<Self, Error> {
+        for (key, _) in DIRECTIONS {
+            controls
+                .position(key)
+                .ok_or(Error::MissingKey(key as char))?;
+        }
+        let mut tables = vec![Table::direct(&controls)];
+        for _ in 0..robots {
+            tables.push(Table::new(&controls, &tables[tables.len() - 1])?);
+        }
+        tables.push(Table::new(&door, &tables[tables.len() - 1])?);
+        Ok(Self {
+            door,
+            controls,
+            tables,
+        })
+    }
+
+    /// The puzzle's numeric door keypad and directional keypads.
+    pub fn standard(robots: usize) -> Self {
+        Self::new(Keypad::numeric(), Keypad::directional(), robots).expect("standard keypads")
+    }
+
+    pub fn door(&self) -> &Keypad {
+        &self.door
+    }
+
+    /// The number of robots at directional keypads.
+    pub fn robots(&self) -> usize {
+        self.tables.len() - 2
+    }
+
+    /// The presses on the keypad one level down that best move the arm at
+    /// `level` from one key to another and press it. Level 0 is the human's
+    /// keypad and the door is at level `robots() + 1`.
+    pub fn best_move(&self, level: usize, from: u8, to: u8) -> Option<&[u8]> {
+        let table = self.tables.get(level)?;
+        table.moves.get(&(from, to)).map(Vec::as_slice)
+    }
+
+    /// The fewest presses the human needs to type `code` on the door keypad,
+    /// saturating at `usize::MAX`.
+    pub fn sequence_length(&self, code: &[u8]) -> Result<usize, Error> {
+        if let Some(&key) = code.iter().find(|&&key| self.door.position(key).is_none()) {
+            return Err(Error::UnknownKey(key as char));
+        }
+        Ok(self.tables[self.tables.len() - 1].sequence_length(code))
+    }
+
+    /// An optimal sequence of presses on the keypad at `level` that types
+    /// `code` on the door, expanded lazily so that deep chains fit in memory.
+    pub fn presses<'a>(&'a self, code: &'a [u8], level: usize) -> Result<Presses<'a>, Error> {
+        let door = self.check(code, level)?;
+        Ok(Presses {
+            robots: self,
+            level,
+            stack: vec![(door, code)],
+            arms: vec![ACTIVATE; door + 1],
+        })
+    }
+
+    /// The presses at `level` as runs of the same key and their lengths.
+    pub fn runs<'a>(
+        &'a self,
+        code: &'a [u8],
+        level: usize,
+    ) -> Result<impl Iterator<Item = (u8, usize)> + 'a, Error> {
+        let mut presses = self.presses(code, level)?.peekable();
+        Ok(std::iter::from_fn(move || {
+            let key = presses.next()?;
+            let mut count = 1;
+            while presses.next_if_eq(&key).is_some() {
+                count += 1;
+            }
+            Some((key, count))
+        }))
+    }
+
+    /// The number of presses at `level` that type `code` on the door, saturating
+    /// at `usize::MAX`.
+    pub fn length(&self, code: &[u8], level: usize) -> Result<usize, Error> {
+        let door = self.check(code, level)?;
+        let mut memo = HashMap::new();
+        Ok(self.expand_sequence(code, door, level, &mut memo))
+    }
+
+    /// Replays presses on the keypad at `level` through the arms above it and
+    /// returns the keys typed on the door.
+    pub fn simulate(
+        &self,
+        level: usize,
+        presses: impl IntoIterator<Item = u8>,
+    ) -> Result<Vec<u8>, Error> {
+        let door = self.check(&[], level)?;
+        let mut arms = vec![ACTIVATE; door + 1];
+        let mut typed = vec![];
+        for key in presses {
+            let (mut level, mut key) = (level, key);
+            loop {
+                if level == door {
+                    self.door
+                        .position(key)
+                        .ok_or(Error::UnknownKey(key as char))?;
+                    typed.push(key);
+                    break;
+                }
+                let above = if level + 1 == door {
+                    &self.door
+                } else {
+                    &self.controls
+                };
+                if let Some(&(_, (dr, dc))) = DIRECTIONS.iter().find(|&&(k, _)| k == key) {
+                    let (row, column) = above.position(arms[level + 1]).expect("arm on a key");
+                    arms[level + 1] = *above
+                        .positions
+                        .get(&(row + dr, column + dc))
+                        .ok_or(Error::ArmOverGap(level + 1))?;
+                    break;
+                } else if key == ACTIVATE {
+                    key = arms[level + 1];
+                    level += 1;
+                } else {
+                    return Err(Error::UnknownKey(key as char));
+                }
+            }
+        }
+        Ok(typed)
+    }
+
+    /// Checks the code and level, returning the door's level.
+    fn check(&self, code: &[u8], level: usize) -> Result<usize, Error> {
+        let door = self.tables.len() - 1;
+        if level > door {
+            return Err(Error::NoLevel(level));
+        }
+        match code.iter().find(|&&key| self.door.position(key).is_none()) {
+            Some(&key) => Err(Error::UnknownKey(key as char)),
+            None => Ok(door),
+        }
+    }
+
+    /// Presses at `target` needed to type `sequence` on the keypad at `level`.
+    fn expand_sequence(
+        &self,
+        sequence: &[u8],
+        level: usize,
+        target: usize,
+        memo: &mut HashMap<(usize, u8, u8), usize>,
+    ) -> usize {
+        if level == target {
+            return sequence.len();
+        }
+        sequence
+            .iter()
+            .fold((ACTIVATE, 0usize), |(previous, total), &key| {
+                let length = match memo.get(&(level, previous, key)) {
+                    Some(&length) => length,
+                    None => {
+                        let moves = &self.tables[level].moves[&(previous, key)];
+                        let length = self.expand_sequence(moves, level - 1, target, memo);
+                        memo.insert((level, previous, key), length);
+                        length
+                    }
+                };
+                (key, total.saturating_add(length))
+            })
+            .1
+    }
+}
+
+/// Presses on one keypad of the chain, expanded from the code on demand.
+#[derive(Debug, Clone)]
+pub struct Presses<'a> {
+    robots: &'a Robots,
+    level: usize,
+    /// Keys still to press at each level above `level`, innermost last.
+    stack: Vec<(usize, &'a [u8])>,
+    /// The key each arm points at, by the level of its keypad.
+    arms: Vec<u8>,
+}
+
+impl Iterator for Presses<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (level, keys) = self.stack.last_mut()?;
+            let level = *level;
+            let Some((&key, rest)) = keys.split_first() else {
+                self.stack.pop();
+                continue;
+            };
+            *keys = rest;
+            if level == self.level {
+                return Some(key);
+            }
+            let from = std::mem::replace(&mut self.arms[level], key);
+            let moves = &self.robots.tables[level].moves[&(from, key)];
+            self.stack.push((level - 1, moves));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes() {
+        let keypad = Keypad::numeric();
+        let mut routes = keypad.routes(b'A', b'1');
+        routes.sort();
+        assert_eq!(routes, [b"<^<A".to_vec(), b"^<<A".to_vec()]);
+        assert_eq!(keypad.routes(b'7', b'7'), [b"A".to_vec()]);
+        assert_eq!(keypad.routes(b'7', b'X'), Vec::<Vec<u8>>::new());
+    }
+
+    /// Breadth-first search over the positions of every arm in the chain.
+    fn brute_force(door: &Keypad, controls: &Keypad, robots: usize, code: &[u8]) -> usize {
+        let start = (vec![ACTIVATE; robots + 1], 0);
+        let mut seen = HashMap::from([(start.clone(), 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(state) = queue.pop_front() {
+            let presses = seen[&state];
+            if state.1 == code.len() {
+                return presses;
+            }
+            for (key, _) in DIRECTIONS.into_iter().chain([(ACTIVATE, (0, 0))]) {
+                let (mut arms, mut typed) = state.clone();
+                let mut key = key;
+                let mut level = 0;
+                let valid = loop {
+                    let keypad = if level == robots { door } else { controls };
+                    if let Some(&(_, (dr, dc))) = DIRECTIONS.iter().find(|(k, _)| *k == key) {
+                        let (row, column) = keypad.position(arms[level]).unwrap();
+                        match keypad.positions.get(&(row + dr, column + dc)) {
+                            Some(&next) => arms[level] = next,
+                            None => break false,
+                        }
+                        break true;
+                    }
+                    if level == robots {
+                        typed += 1;
+                        break code[typed - 1] == arms[level];
+                    }
+                    key = arms[level];
+                    level += 1;
+                };
+                if valid && !seen.contains_key(&(arms.clone(), typed)) {
+                    seen.insert((arms.clone(), typed), presses + 1);
+                    queue.push_back((arms, typed));
+                }
+            }
+        }
+        usize::MAX
+    }
+
+    #[test]
+    fn standard() {
+        let robots = Robots::standard(2);
+        assert_eq!(robots.sequence_length(b"029A"), Ok(68));
+        assert_eq!(robots.sequence_length(b"379A"), Ok(64));
+        assert_eq!(robots.best_move(3, b'A', b'0'), Some(&b"<A"[..]));
+        assert_eq!(robots.best_move(2, b'A', b'<'), Some(&b"v<<A"[..]));
+        assert_eq!(robots.best_move(0, b'A', b'<'), Some(&b"<"[..]));
+        assert_eq!(Robots::standard(1).sequence_length(b"029A"), Ok(28));
+        assert_eq!(Robots::standard(0).sequence_length(b"029A"), Ok(12));
+        assert_eq!(robots.sequence_length(b"02B"), Err(Error::UnknownKey('B')));
+    }
+
+    #[test]
+    fn presses() {
+        let robots = Robots::standard(2);
+        for code in [&b"029A"[..], b"980A", b"179A", b"456A", b"379A"] {
+            for level in 0..=3 {
+                let presses = robots.presses(code, level).unwrap().collect::<Vec<_>>();
+                assert_eq!(presses.len(), robots.length(code, level).unwrap());
+                assert_eq!(robots.simulate(level, presses).unwrap(), code);
+            }
+            let runs = robots.runs(code, 0).unwrap().collect::<Vec<_>>();
+            let presses = runs.iter().flat_map(|&(key, count)| vec![key; count]);
+            assert_eq!(robots.simulate(0, presses).unwrap(), code);
+        }
+        assert_eq!(
+            robots.presses(b"029A", 3).unwrap().collect::<Vec<_>>(),
+            b"029A"
+        );
+        assert_eq!(
+            robots.presses(b"029A", 2).unwrap().collect::<Vec<_>>(),
+            b"<A^A>^^AvvvA"
+        );
+
+        let deep = Robots::standard(25);
+        assert_eq!(deep.length(b"029A", 0), deep.sequence_length(b"029A"));
+        let start = deep
+            .presses(b"029A", 0)
+            .unwrap()
+            .take(100_000)
+            .collect::<Vec<_>>();
+        assert_eq!(deep.runs(b"029A", 0).unwrap().next(), Some((start[0], 1)));
+        assert!(deep.simulate(0, start).unwrap().len() < 4);
+
+        assert_eq!(robots.presses(b"029A", 4).err(), Some(Error::NoLevel(4)));
+        assert_eq!(
+            Robots::standard(0).simulate(0, *b"<<"),
+            Err(Error::ArmOverGap(1))
+        );
+        assert_eq!(robots.simulate(0, *b"<x"), Err(Error::UnknownKey('x')));
+    }
+
+    #[test]
+    fn custom() {
+        let doors = [
+            Keypad::numeric(),
+            // Upside down, with the gap in the top left.
+            " 0A\n123\n456\n789".parse().unwrap(),
+            // Phone order.
+            "123\n456\n789\n 0A".parse().unwrap(),
+            // A gap in the middle.
+            "123\n4 6\n78A".parse().unwrap(),
+        ];
+        let controls = [
+            Keypad::directional(),
+            "<v>\n ^A".parse().unwrap(),
+            "<^Av>".parse().unwrap(),
+        ];
+        for door in &doors {
+            for controls in &controls {
+                let mut compared = 0;
+                for robots in 0..=2 {
+                    let chain = Robots::new(door.clone(), controls.clone(), robots).unwrap();
+                    for code in [
+                        &b"029A"[..],
+                        b"980A",
+                        b"179A",
+                        b"456A",
+                        b"379A",
+                        b"13A",
+                        b"78A",
+                    ] {
+                        let missing = code.iter().find(|&&key| door.position(key).is_none());
+                        match missing {
+                            Some(&key) => assert_eq!(
+                                chain.sequence_length(code),
+                                Err(Error::UnknownKey(key as char))
+                            ),
+                            None => {
+                                assert_eq!(
+                                    chain.sequence_length(code),
+                                    Ok(brute_force(door, controls, robots, code))
+                                );
+                                compared += 1;
+                            }
+                        }
+                    }
+                }
+                assert!(compared > 0, "{door:?}");
+            }
+        }
+
+        assert_eq!("12\n1A".parse::<Keypad>(), Err(Error::DuplicateKey('1')));
+        assert_eq!("12".parse::<Keypad>(), Err(Error::MissingKey('A')));
+        assert_eq!(
+            Robots::new(Keypad::numeric(), "A^v".parse().unwrap(), 1),
+            Err(Error::MissingKey('>'))
+        );
+        assert_eq!(
+            Robots::new("A 1".parse().unwrap(), Keypad::directional(), 1),
+            Err(Error::Unreachable('A', '1'))
+        );
+    }
+}
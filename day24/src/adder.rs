@@ -0,0 +1,248 @@
+use crate::{
+    circuit::{self, Circuit},
+    spec::{self, Spec, RANDOM_CASES},
+};
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Invalid circuit: {0}")]
+    InvalidCircuit(#[from] circuit::Error),
+    #[error("Cannot pair up an odd number of wires: {0}")]
+    OddNumberOfWires(usize),
+    #[error("Too many wires to try every pairing: {0}")]
+    TooManyWires(usize),
+}
+
+/// The outcome of swapping pairs of gate outputs to repair an adder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairReport {
+    pub swaps: Vec<(String, String)>,
+    /// Output bits that were wrong for at least one addition before the swaps.
+    pub failing_before: Vec<usize>,
+    /// Output bits that were wrong for at least one addition after the swaps.
+    pub failing_after: Vec<usize>,
+}
+
+impl RepairReport {
+    pub fn is_correct(&self) -> bool {
+        self.failing_after.is_empty()
+    }
+}
+
+impl fmt::Display for RepairReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits = |bits: &[usize]| {
+            if bits.is_empty() {
+                "none".to_string()
+            } else {
+                bits.iter()
+                    .map(|bit| format!("z{bit:02}"))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }
+        };
+        for (a, b) in &self.swaps {
+            writeln!(f, "swap {a} <-> {b}")?;
+        }
+        writeln!(f, "failing before: {}", bits(&self.failing_before))?;
+        writeln!(f, "failing after: {}", bits(&self.failing_after))
+    }
+}
+
+/// Output bits where the circuit does not compute `z = x + y`.
+pub fn failing_bits(circuit: &Circuit, random_cases: usize) -> Result<Vec<usize>, circuit::Error> {
+    spec::failing_bits(circuit, &Spec::Add, random_cases)
+}
+
+/// Tries every way of pairing up `wires` as output swaps and reports the best one.
+///
+/// The first pairing that passes [`failing_bits`] is returned. If none does, the
+/// report holds the pairing with the fewest failing bits, or no swaps at all if
+/// every pairing leaves at least as many bits failing as before. Every pairing is
+/// checked with the same inputs as the circuit before the swaps, so at most
+/// [`MAX_WIRES`] wires are accepted.
+pub fn repair(circuit: &Circuit, wires: &[&str]) -> Result<RepairReport, Error> {
+    if wires.len() % 2 == 1 {
+        return Err(Error::OddNumberOfWires(wires.len()));
+    }
+    if wires.len() > MAX_WIRES {
+        return Err(Error::TooManyWires(wires.len()));
+    }
+    let ids = wires
+        .iter()
+        .map(|&wire| {
+            circuit
+                .wire(wire)
+                .ok_or_else(|| circuit::Error::UnknownWire(wire.to_owned()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let failing_before = failing_bits(circuit, RANDOM_CASES)?;
+    let mut best = RepairReport {
+        swaps: vec![],
+        failing_before: failing_before.clone(),
+        failing_after: failing_before,
+    };
+    let mut working = circuit.clone();
+    let mut visit = |candidate: &Circuit, pairing: &[(usize, usize)]| {
+        // Swaps that create a cycle cannot be right.
+        let Ok(failing) = failing_bits(candidate, RANDOM_CASES) else {
+            return Ok(false);
+        };
+        if failing.len() < best.failing_after.len() {
+            best.swaps = pairing
+                .iter()
+                .map(|&(a, b)| (candidate.name(a), candidate.name(b)))
+                .map(|(a, b)| (a.to_owned(), b.to_owned()))
+                .collect();
+            best.failing_after = failing;
+        }
+        Ok(best.is_correct())
+    };
+    for_each_pairing(&mut working, &ids, &mut vec![], &mut visit)?;
+    Ok(best)
+}
+
+/// The most wires [`repair`] will pair up, giving 945 pairings to simulate. Two more
+/// wires would mean 10395.
+pub const MAX_WIRES: usize = 10;
+
+/// Applies each way of pairing up `wires` as output swaps to `circuit` in turn and
+/// calls `visit` with it, undoing the swaps afterwards. Stops early and returns
+/// `true` once `visit` does.
+fn for_each_pairing<F>(
+    circuit: &mut Circuit,
+    wires: &[usize],
+    pairing: &mut Vec<(usize, usize)>,
+    visit: &mut F,
+) -> Result<bool, circuit::Error>
+where
+    F: FnMut(&Circuit, &[(usize, usize)]) -> Result<bool, circuit::Error>,
+{
+    let Some((&first, rest)) = wires.split_first() else {
+        return visit(circuit, pairing);
+    };
+    let mut others = rest.to_vec();
+    for index in 0..rest.len() {
+        let partner = others.remove(index);
+        circuit.swap_output_wires(first, partner)?;
+        pairing.push((first, partner));
+        let done = for_each_pairing(circuit, &others, pairing, visit);
+        pairing.pop();
+        circuit.swap_output_wires(first, partner)?;
+        others.insert(index, partner);
+        if done? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// The puzzle's ripple-carry adder layout for `bits` bit inputs.
+#[cfg(test)]
+pub(crate) fn ripple_carry_adder(bits: usize) -> String {
+    let mut gates = vec![
+        "x00 XOR y00 -> z00".to_string(),
+        "x00 AND y00 -> c00".to_string(),
+    ];
+    for bit in 1..bits {
+        let carry = if bit == bits - 1 {
+            format!("z{bits:02}")
+        } else {
+            format!("c{bit:02}")
+        };
+        gates.extend([
+            format!("x{bit:02} XOR y{bit:02} -> s{bit:02}"),
+            format!("x{bit:02} AND y{bit:02} -> a{bit:02}"),
+            format!("s{bit:02} XOR c{:02} -> z{bit:02}", bit - 1),
+            format!("s{bit:02} AND c{:02} -> b{bit:02}", bit - 1),
+            format!("a{bit:02} OR b{bit:02} -> {carry}"),
+        ]);
+    }
+    gates.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correct_adder() {
+        let circuit = Circuit::parse(&ripple_carry_adder(8)).unwrap();
+        assert_eq!(failing_bits(&circuit, 100), Ok(vec![]));
+        let report = repair(&circuit, &[]).unwrap();
+        assert!(report.is_correct());
+        assert!(report.swaps.is_empty());
+    }
+
+    #[test]
+    fn repair_swaps() {
+        let mut circuit = Circuit::parse(&ripple_carry_adder(8)).unwrap();
+        circuit.swap_outputs("z03", "b03").unwrap();
+        circuit.swap_outputs("s05", "a05").unwrap();
+        assert_eq!(failing_bits(&circuit, 100), Ok(vec![3, 4, 5, 6, 7, 8]));
+
+        let report = repair(&circuit, &["a05", "b03", "s05", "z03"]).unwrap();
+        assert!(report.is_correct());
+        assert_eq!(
+            report.swaps,
+            [
+                ("a05".to_string(), "s05".to_string()),
+                ("b03".to_string(), "z03".to_string())
+            ]
+        );
+        assert_eq!(report.failing_before, [3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            report.to_string(),
+            "swap a05 <-> s05\nswap b03 <-> z03\n\
+             failing before: z03,z04,z05,z06,z07,z08\nfailing after: none\n"
+        );
+    }
+
+    #[test]
+    fn wrong_suspects() {
+        let mut circuit = Circuit::parse(&ripple_carry_adder(4)).unwrap();
+        circuit.swap_outputs("z02", "b02").unwrap();
+        let report = repair(&circuit, &["a01", "s01"]).unwrap();
+        assert!(!report.is_correct());
+        // Swapping them breaks more bits, so nothing is recommended.
+        assert!(report.swaps.is_empty());
+        assert_eq!(report.failing_after, report.failing_before);
+        assert_eq!(repair(&circuit, &["z02"]), Err(Error::OddNumberOfWires(1)));
+    }
+
+    #[test]
+    fn pairings_count() {
+        let count = |wires: usize, stop_at: usize| {
+            let mut circuit = Circuit::parse(&ripple_carry_adder(16)).unwrap();
+            let ids = (1..=wires)
+                .map(|bit| circuit.wire(&format!("z{bit:02}")).unwrap())
+                .collect::<Vec<_>>();
+            let mut count = 0;
+            let stopped = for_each_pairing(&mut circuit, &ids, &mut vec![], &mut |_, _| {
+                count += 1;
+                Ok(count == stop_at)
+            })
+            .unwrap();
+            (count, stopped)
+        };
+        assert_eq!(count(4, 0), (3, false));
+        assert_eq!(count(8, 0), (105, false));
+        // Pairings are made one at a time, so stopping early skips the rest.
+        assert_eq!(count(MAX_WIRES, 10), (10, true));
+    }
+
+    #[test]
+    fn repair_many_wires() {
+        // No pairing of these fixes the adder, so every one of them is simulated.
+        let mut circuit = Circuit::parse(&ripple_carry_adder(16)).unwrap();
+        circuit.swap_outputs("z03", "b03").unwrap();
+        let wires = (1..=12).map(|bit| format!("a{bit:02}")).collect::<Vec<_>>();
+        let wires = wires.iter().map(String::as_str).collect::<Vec<_>>();
+        let report = repair(&circuit, &wires[..MAX_WIRES]).unwrap();
+        assert!(!report.is_correct());
+        assert_eq!(report.failing_after, report.failing_before);
+        assert_eq!(repair(&circuit, &wires), Err(Error::TooManyWires(12)));
+    }
+}
@@ -0,0 +1,42 @@
+use day24::{
+    circuit::Circuit,
+    part2,
+    spec::{self, Limits},
+};
+use std::{error::Error, fs};
+
+const USAGE: &str = "\
+Usage: day24 repair <file>
+       day24 swaps <file> [add|and|mul]
+       day24 dot <file>
+       day24 verilog <file> [module]";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let (command, path, option) = match args.as_slice() {
+        [command, path] => (command.as_str(), path, None),
+        [command, path, option] => (command.as_str(), path, Some(option.as_str())),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    let input = fs::read_to_string(path)?;
+    let circuit = Circuit::parse(&input)?;
+    match command {
+        "repair" => print!("{}", part2::repair(&input)?),
+        "dot" => print!("{}", circuit.to_dot()),
+        "swaps" => {
+            let spec = option.unwrap_or("add").parse()?;
+            for (a, b) in spec::find_swaps(&circuit, &spec, Limits::default())? {
+                println!("swap {a} <-> {b}");
+            }
+        }
+        "verilog" => print!("{}", circuit.to_verilog(option.unwrap_or("circuit"))),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
+    Ok(())
+}
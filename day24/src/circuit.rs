@@ -0,0 +1,723 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Missing input value delimiter")]
+    MissingInputValueDelimiter,
+    #[error("Missing gate output delimiter")]
+    MissingGateOutputDelimiter,
+    #[error("Missing gate left hand side")]
+    MissingGateLeftHandSide,
+    #[error("Missing gate operation")]
+    MissingGateOperation,
+    #[error("Invalid gate operation: {0}")]
+    InvalidGateOperation(String),
+    #[error("Missing gate right hand side")]
+    MissingGateRightHandSide,
+    #[error("Non boolean value: {0}")]
+    NonBooleanValue(String),
+    #[error("Wire {0} is driven by more than one gate")]
+    MultipleDrivers(String),
+    #[error("Wire {0} has no value and is not driven by a gate")]
+    UndrivenWire(String),
+    #[error("Unknown wire: {0}")]
+    UnknownWire(String),
+    #[error("Bus {0} has more than 128 bits")]
+    BusTooWide(char),
+    #[error("Circuit contains a cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    And,
+    Or,
+    Xor,
+}
+
+impl Operation {
+    pub fn apply(self, lhs: bool, rhs: bool) -> bool {
+        match self {
+            Operation::And => lhs & rhs,
+            Operation::Or => lhs | rhs,
+            Operation::Xor => lhs ^ rhs,
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Operation::And => "AND",
+            Operation::Or => "OR",
+            Operation::Xor => "XOR",
+        })
+    }
+}
+
+/// A gate with its wires given as indices into [`Circuit::wires`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gate {
+    pub lhs: usize,
+    pub rhs: usize,
+    pub output: usize,
+    pub operation: Operation,
+}
+
+/// A netlist of logic gates, with optional initial values for its input wires.
+#[derive(Debug, Clone, Default)]
+pub struct Circuit {
+    wires: Vec<String>,
+    ids: HashMap<String, usize>,
+    gates: Vec<Gate>,
+    /// The index of the gate driving each wire.
+    drivers: Vec<Option<usize>>,
+    values: HashMap<usize, bool>,
+}
+
+impl Circuit {
+    /// Parses the puzzle input: lines of `name: value` and lines of `lhs OP rhs -> output`.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let mut circuit = Self::default();
+        for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if line.contains(" -> ") {
+                circuit.parse_gate(line)?;
+            } else {
+                let (name, value) = line
+                    .split_once(": ")
+                    .ok_or(Error::MissingInputValueDelimiter)?;
+                let value = match value {
+                    "1" => true,
+                    "0" => false,
+                    _ => return Err(Error::NonBooleanValue(value.to_owned())),
+                };
+                let wire = circuit.wire_id(name);
+                circuit.values.insert(wire, value);
+            }
+        }
+        Ok(circuit)
+    }
+
+    fn parse_gate(&mut self, line: &str) -> Result<(), Error> {
+        let (gate, output) = line
+            .split_once(" -> ")
+            .ok_or(Error::MissingGateOutputDelimiter)?;
+        let mut parts = gate.split_whitespace();
+        let lhs = parts.next().ok_or(Error::MissingGateLeftHandSide)?;
+        let operation = match parts.next().ok_or(Error::MissingGateOperation)? {
+            "AND" => Operation::And,
+            "OR" => Operation::Or,
+            "XOR" => Operation::Xor,
+            op => return Err(Error::InvalidGateOperation(op.to_owned())),
+        };
+        let rhs = parts.next().ok_or(Error::MissingGateRightHandSide)?;
+        let gate = Gate {
+            lhs: self.wire_id(lhs),
+            rhs: self.wire_id(rhs),
+            output: self.wire_id(output.trim()),
+            operation,
+        };
+        if self.drivers[gate.output].is_some() {
+            return Err(Error::MultipleDrivers(self.wires[gate.output].clone()));
+        }
+        self.drivers[gate.output] = Some(self.gates.len());
+        self.gates.push(gate);
+        Ok(())
+    }
+
+    fn wire_id(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.wires.len();
+        self.wires.push(name.to_owned());
+        self.drivers.push(None);
+        self.ids.insert(name.to_owned(), id);
+        id
+    }
+
+    /// The names of all wires, indexed by wire id.
+    pub fn wires(&self) -> &[String] {
+        &self.wires
+    }
+
+    pub fn wire(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, wire: usize) -> &str {
+        &self.wires[wire]
+    }
+
+    pub fn gates(&self) -> &[Gate] {
+        &self.gates
+    }
+
+    /// The gate driving a wire, if any.
+    pub fn driver(&self, wire: usize) -> Option<&Gate> {
+        self.drivers
+            .get(wire)
+            .copied()
+            .flatten()
+            .map(|index| &self.gates[index])
+    }
+
+    /// The initial value of a wire given in the input.
+    pub fn value(&self, wire: usize) -> Option<bool> {
+        self.values.get(&wire).copied()
+    }
+
+    /// Wires that are not driven by any gate.
+    pub fn inputs(&self) -> Vec<usize> {
+        let mut inputs = (0..self.wires.len())
+            .filter(|&wire| self.drivers[wire].is_none())
+            .collect::<Vec<_>>();
+        inputs.sort_by(|&a, &b| self.wires[a].cmp(&self.wires[b]));
+        inputs
+    }
+
+    /// The wires named by a letter and a bit number, such as `x00`, least significant first.
+    ///
+    /// Missing bits are `None`, so the position in the result is the bit number.
+    pub fn bus(&self, prefix: char) -> Vec<Option<usize>> {
+        let mut bus = vec![];
+        for (id, name) in self.wires.iter().enumerate() {
+            let Some(bit) = name
+                .strip_prefix(prefix)
+                .and_then(|digits| digits.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            if bus.len() <= bit {
+                bus.resize(bit + 1, None);
+            }
+            bus[bit] = Some(id);
+        }
+        bus
+    }
+
+    /// Gate indices ordered so that every gate comes after the gates driving its inputs.
+    pub fn topological_order(&self) -> Result<Vec<usize>, Error> {
+        let mut readers = vec![vec![]; self.wires.len()];
+        let mut pending = vec![0; self.gates.len()];
+        for (index, gate) in self.gates.iter().enumerate() {
+            for wire in [gate.lhs, gate.rhs] {
+                readers[wire].push(index);
+                if self.drivers[wire].is_some() {
+                    pending[index] += 1;
+                }
+            }
+        }
+
+        let mut ready = (0..self.gates.len())
+            .filter(|&index| pending[index] == 0)
+            .collect::<Vec<_>>();
+        let mut order = Vec::with_capacity(self.gates.len());
+        while let Some(index) = ready.pop() {
+            order.push(index);
+            for &reader in &readers[self.gates[index].output] {
+                pending[reader] -= 1;
+                if pending[reader] == 0 {
+                    ready.push(reader);
+                }
+            }
+        }
+
+        if order.len() == self.gates.len() {
+            Ok(order)
+        } else {
+            Err(Error::Cycle(self.find_cycle(&pending)))
+        }
+    }
+
+    /// Follows unresolved inputs backwards from a gate that is stuck until a wire repeats.
+    fn find_cycle(&self, pending: &[usize]) -> Vec<String> {
+        let Some(mut index) = (0..self.gates.len()).find(|&index| pending[index] > 0) else {
+            return vec![];
+        };
+        let mut path: Vec<usize> = vec![];
+        loop {
+            let gate = &self.gates[index];
+            if let Some(start) = path.iter().position(|&wire| wire == gate.output) {
+                let mut cycle = path[start..]
+                    .iter()
+                    .rev()
+                    .map(|&wire| self.wires[wire].clone())
+                    .collect::<Vec<_>>();
+                cycle.push(cycle[0].clone());
+                return cycle;
+            }
+            path.push(gate.output);
+            index = [gate.lhs, gate.rhs]
+                .into_iter()
+                .filter_map(|wire| self.drivers[wire])
+                .find(|&driver| pending[driver] > 0)
+                .expect("a stuck gate has a stuck input");
+        }
+    }
+
+    /// Evaluates every wire, taking input values from `input`.
+    pub fn evaluate(&self, input: impl Fn(usize) -> Option<bool>) -> Result<Vec<bool>, Error> {
+        let order = self.topological_order()?;
+        let mut values = vec![None; self.wires.len()];
+        for wire in self.inputs() {
+            values[wire] =
+                Some(input(wire).ok_or_else(|| Error::UndrivenWire(self.wires[wire].clone()))?);
+        }
+        for index in order {
+            let gate = &self.gates[index];
+            let lhs = values[gate.lhs].expect("inputs are evaluated first");
+            let rhs = values[gate.rhs].expect("inputs are evaluated first");
+            values[gate.output] = Some(gate.operation.apply(lhs, rhs));
+        }
+        Ok(values.into_iter().map(Option::unwrap_or_default).collect())
+    }
+
+    /// The number on the `z` wires using the initial values from the input.
+    pub fn run(&self) -> Result<u128, Error> {
+        let values = self.evaluate(|wire| self.value(wire))?;
+        self.read_bus('z', &values)
+    }
+
+    /// The number on the `z` wires when the `x` and `y` wires hold the given numbers.
+    ///
+    /// Inputs other than `x` and `y` keep their initial values.
+    pub fn simulate(&self, x: u128, y: u128) -> Result<u128, Error> {
+        Ok(self.simulator()?.run(x, y))
+    }
+
+    /// A simulator for running the circuit on many inputs without re-ordering the gates.
+    ///
+    /// Like [`Circuit::evaluate`], every input other than the `x` and `y` wires
+    /// needs an initial value.
+    pub fn simulator(&self) -> Result<Simulator, Error> {
+        let [x, y, z] = ['x', 'y', 'z'].map(|prefix| self.bus(prefix));
+        for (prefix, bus) in [('x', &x), ('y', &y), ('z', &z)] {
+            if bus.len() > 128 {
+                return Err(Error::BusTooWide(prefix));
+            }
+        }
+        let is_operand = |wire| x.contains(&Some(wire)) || y.contains(&Some(wire));
+        if let Some(wire) = self
+            .inputs()
+            .into_iter()
+            .find(|&wire| !is_operand(wire) && !self.values.contains_key(&wire))
+        {
+            return Err(Error::UndrivenWire(self.wires[wire].clone()));
+        }
+        let mut values = vec![0; self.wires.len()];
+        for (&wire, &value) in &self.values {
+            values[wire] = if value { u128::MAX } else { 0 };
+        }
+        Ok(Simulator {
+            gates: self
+                .topological_order()?
+                .into_iter()
+                .map(|index| self.gates[index])
+                .collect(),
+            x,
+            y,
+            z,
+            values,
+        })
+    }
+
+    /// Exchanges the output wires of the gates driving `a` and `b`.
+    pub fn swap_outputs(&mut self, a: &str, b: &str) -> Result<(), Error> {
+        let id = |wire: &str| {
+            self.wire(wire)
+                .ok_or_else(|| Error::UnknownWire(wire.to_owned()))
+        };
+        let (a, b) = (id(a)?, id(b)?);
+        self.swap_output_wires(a, b)
+    }
+
+    /// Like [`Circuit::swap_outputs`] with wire ids.
+    pub fn swap_output_wires(&mut self, a: usize, b: usize) -> Result<(), Error> {
+        let index = |wire: usize| {
+            self.drivers[wire].ok_or_else(|| Error::UndrivenWire(self.wires[wire].clone()))
+        };
+        let (gate_a, gate_b) = (index(a)?, index(b)?);
+        self.gates[gate_a].output = b;
+        self.gates[gate_b].output = a;
+        self.drivers[a] = Some(gate_b);
+        self.drivers[b] = Some(gate_a);
+        Ok(())
+    }
+
+    /// The outputs of every gate that the value of `wire` depends on, including its own
+    /// driver, in order of wire id.
+    pub fn fan_in(&self, wire: usize) -> Vec<usize> {
+        let mut seen = vec![false; self.wires.len()];
+        let mut stack = vec![wire];
+        let mut cone = vec![];
+        while let Some(wire) = stack.pop() {
+            if std::mem::replace(&mut seen[wire], true) {
+                continue;
+            }
+            if let Some(gate) = self.driver(wire) {
+                cone.push(wire);
+                stack.extend([gate.lhs, gate.rhs]);
+            }
+        }
+        cone.sort_unstable();
+        cone
+    }
+
+    /// Reads a bus as a number from the values returned by [`Circuit::evaluate`].
+    pub fn read_bus(&self, prefix: char, values: &[bool]) -> Result<u128, Error> {
+        let bus = self.bus(prefix);
+        if bus.len() > 128 {
+            return Err(Error::BusTooWide(prefix));
+        }
+        Ok(bus
+            .into_iter()
+            .enumerate()
+            .filter(|(_, wire)| wire.is_some_and(|wire| values[wire]))
+            .fold(0, |acc, (bit, _)| acc | 1 << bit))
+    }
+
+    /// The netlist as a Graphviz digraph, with one node per wire.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n");
+        for wire in self.inputs() {
+            writeln!(dot, "    \"{}\" [shape=box];", self.wires[wire]).unwrap();
+        }
+        for gate in &self.gates {
+            let name = &self.wires[gate.output];
+            let shape = match gate.operation {
+                Operation::And => "invhouse",
+                Operation::Or => "ellipse",
+                Operation::Xor => "diamond",
+            };
+            let peripheries = if name.starts_with('z') { 2 } else { 1 };
+            writeln!(
+                dot,
+                "    \"{name}\" [label=\"{name}\\n{}\", shape={shape}, peripheries={peripheries}];",
+                gate.operation
+            )
+            .unwrap();
+        }
+        for gate in &self.gates {
+            for wire in [gate.lhs, gate.rhs] {
+                writeln!(
+                    dot,
+                    "    \"{}\" -> \"{}\";",
+                    self.wires[wire], self.wires[gate.output]
+                )
+                .unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// The netlist as a structural Verilog module built from gate primitives.
+    ///
+    /// Undriven wires become inputs and `z` wires become outputs.
+    pub fn to_verilog(&self, module: &str) -> String {
+        let inputs = self
+            .inputs()
+            .into_iter()
+            .map(|wire| self.wires[wire].as_str())
+            .collect::<Vec<_>>();
+        let mut outputs = self
+            .gates
+            .iter()
+            .map(|gate| self.wires[gate.output].as_str())
+            .filter(|name| name.starts_with('z'))
+            .collect::<Vec<_>>();
+        outputs.sort();
+        let mut internal = self
+            .gates
+            .iter()
+            .map(|gate| self.wires[gate.output].as_str())
+            .filter(|name| !name.starts_with('z'))
+            .collect::<Vec<_>>();
+        internal.sort();
+
+        let mut verilog = String::new();
+        let ports = inputs.iter().chain(&outputs).copied().collect::<Vec<_>>();
+        writeln!(verilog, "module {module}({});", ports.join(", ")).unwrap();
+        for (keyword, wires) in [
+            ("input", &inputs),
+            ("output", &outputs),
+            ("wire", &internal),
+        ] {
+            if !wires.is_empty() {
+                writeln!(verilog, "    {keyword} {};", wires.join(", ")).unwrap();
+            }
+        }
+        for (index, gate) in self.gates.iter().enumerate() {
+            let primitive = match gate.operation {
+                Operation::And => "and",
+                Operation::Or => "or",
+                Operation::Xor => "xor",
+            };
+            writeln!(
+                verilog,
+                "    {primitive} g{index}({}, {}, {});",
+                self.wires[gate.output], self.wires[gate.lhs], self.wires[gate.rhs]
+            )
+            .unwrap();
+        }
+        verilog.push_str("endmodule\n");
+        verilog
+    }
+}
+
+/// Writes the circuit back out in the puzzle input format.
+impl fmt::Display for Circuit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut values = self.values.iter().collect::<Vec<_>>();
+        values.sort_by_key(|(&wire, _)| &self.wires[wire]);
+        for (&wire, &value) in &values {
+            writeln!(f, "{}: {}", self.wires[wire], u8::from(value))?;
+        }
+        writeln!(f)?;
+        for gate in &self.gates {
+            writeln!(
+                f,
+                "{} {} {} -> {}",
+                self.wires[gate.lhs], gate.operation, self.wires[gate.rhs], self.wires[gate.output]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs a circuit with its gates in topological order.
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    gates: Vec<Gate>,
+    x: Vec<Option<usize>>,
+    y: Vec<Option<usize>>,
+    z: Vec<Option<usize>>,
+    /// The value of each wire in up to 128 simulations at once, one per bit.
+    values: Vec<u128>,
+}
+
+impl Simulator {
+    /// The number on the `z` wires when the `x` and `y` wires hold the given numbers.
+    pub fn run(&mut self, x: u128, y: u128) -> u128 {
+        self.run_batch(&[(x, y)])[0]
+    }
+
+    /// Like [`Simulator::run`] for many `(x, y)` pairs, simulating 128 at a time.
+    pub fn run_batch(&mut self, cases: &[(u128, u128)]) -> Vec<u128> {
+        let mut results = Vec::with_capacity(cases.len());
+        for chunk in cases.chunks(128) {
+            let xs = chunk.iter().map(|case| case.0).collect::<Vec<_>>();
+            let ys = chunk.iter().map(|case| case.1).collect::<Vec<_>>();
+            for (bus, numbers) in [(&self.x, xs), (&self.y, ys)] {
+                for (bit, wire) in bus.iter().enumerate() {
+                    if let Some(wire) = *wire {
+                        self.values[wire] = numbers
+                            .iter()
+                            .enumerate()
+                            .fold(0, |acc, (lane, number)| acc | (number >> bit & 1) << lane);
+                    }
+                }
+            }
+            for gate in &self.gates {
+                let (lhs, rhs) = (self.values[gate.lhs], self.values[gate.rhs]);
+                self.values[gate.output] = match gate.operation {
+                    Operation::And => lhs & rhs,
+                    Operation::Or => lhs | rhs,
+                    Operation::Xor => lhs ^ rhs,
+                };
+            }
+            results.extend((0..chunk.len()).map(|lane| {
+                self.z
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, wire)| wire.is_some_and(|wire| self.values[wire] >> lane & 1 == 1))
+                    .fold(0, |acc, (bit, _)| acc | 1 << bit)
+            }));
+        }
+        results
+    }
+
+    /// The number of bits on the `x`, `y` and `z` buses.
+    pub fn widths(&self) -> (usize, usize, usize) {
+        (self.x.len(), self.y.len(), self.z.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+    const EXAMPLE2: &str = include_str!("../example2.txt");
+
+    /// A two bit ripple-carry adder.
+    const ADDER: &str = "\
+x00 XOR y00 -> z00
+x00 AND y00 -> c00
+x01 XOR y01 -> s01
+x01 AND y01 -> a01
+s01 XOR c00 -> z01
+s01 AND c00 -> b01
+a01 OR b01 -> z02
+";
+
+    #[test]
+    fn run_examples() {
+        assert_eq!(Circuit::parse(EXAMPLE).unwrap().run(), Ok(4));
+        assert_eq!(Circuit::parse(EXAMPLE2).unwrap().run(), Ok(2024));
+    }
+
+    #[test]
+    fn simulate_adder() {
+        let circuit = Circuit::parse(ADDER).unwrap();
+        for x in 0..4 {
+            for y in 0..4 {
+                assert_eq!(circuit.simulate(x, y), Ok(x + y));
+            }
+        }
+        let cases = (0..200).map(|n| (n % 4, n / 4 % 4)).collect::<Vec<_>>();
+        let results = circuit.simulator().unwrap().run_batch(&cases);
+        assert!(cases
+            .iter()
+            .zip(results)
+            .all(|(&(x, y), result)| result == x + y));
+        assert_eq!(circuit.bus('x').len(), 2);
+        assert_eq!(circuit.bus('z').len(), 3);
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let circuit = Circuit::parse(EXAMPLE).unwrap();
+        assert_eq!(circuit.to_string(), EXAMPLE);
+    }
+
+    #[test]
+    fn topological_order() {
+        let circuit = Circuit::parse(EXAMPLE2).unwrap();
+        let order = circuit.topological_order().unwrap();
+        assert_eq!(order.len(), circuit.gates().len());
+        let mut seen = circuit
+            .inputs()
+            .into_iter()
+            .map(|wire| circuit.name(wire))
+            .collect::<Vec<_>>();
+        for index in order {
+            let gate = circuit.gates()[index];
+            assert!(seen.contains(&circuit.name(gate.lhs)));
+            assert!(seen.contains(&circuit.name(gate.rhs)));
+            seen.push(circuit.name(gate.output));
+        }
+    }
+
+    #[test]
+    fn fan_in() {
+        let circuit = Circuit::parse(ADDER).unwrap();
+        let cone = circuit
+            .fan_in(circuit.wire("z01").unwrap())
+            .into_iter()
+            .map(|wire| circuit.name(wire))
+            .collect::<Vec<_>>();
+        assert_eq!(cone, ["c00", "s01", "z01"]);
+        assert!(circuit.fan_in(circuit.wire("x00").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn cycle() {
+        let circuit = Circuit::parse("x00 AND b -> a\na OR y00 -> b\nb XOR x00 -> z00").unwrap();
+        assert_eq!(
+            circuit.topological_order(),
+            Err(Error::Cycle(vec![
+                "b".to_string(),
+                "a".to_string(),
+                "b".to_string()
+            ]))
+        );
+        assert!(matches!(circuit.simulate(0, 0), Err(Error::Cycle(_))));
+    }
+
+    #[test]
+    fn swap_outputs() {
+        let mut circuit = Circuit::parse(ADDER).unwrap();
+        circuit.swap_outputs("z01", "c00").unwrap();
+        let (z01, c00) = (circuit.wire("z01").unwrap(), circuit.wire("c00").unwrap());
+        assert_eq!(circuit.driver(z01).unwrap().operation, Operation::And);
+        assert_eq!(circuit.driver(c00).unwrap().operation, Operation::Xor);
+        assert_eq!(circuit.fan_in(z01), [z01]);
+        assert_eq!(
+            circuit.swap_outputs("z01", "x00"),
+            Err(Error::UndrivenWire("x00".to_string()))
+        );
+    }
+
+    #[test]
+    fn wide_buses() {
+        for prefix in ['x', 'y', 'z'] {
+            let input = (0..129)
+                .map(|bit| match prefix {
+                    'z' => format!("x00 AND y00 -> z{bit:03}"),
+                    _ => format!("{prefix}{bit:03} AND {prefix}{bit:03} -> w{bit:03}"),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let circuit = Circuit::parse(&input).unwrap();
+            assert_eq!(circuit.simulator().err(), Some(Error::BusTooWide(prefix)));
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Circuit::parse("x00 AND y00 -> z00\nx00 OR y00 -> z00").err(),
+            Some(Error::MultipleDrivers("z00".to_string()))
+        );
+        assert_eq!(
+            Circuit::parse("x00 NAND y00 -> z00").err(),
+            Some(Error::InvalidGateOperation("NAND".to_string()))
+        );
+        assert_eq!(
+            Circuit::parse("x00: 2").err(),
+            Some(Error::NonBooleanValue("2".to_string()))
+        );
+        assert_eq!(
+            Circuit::parse("x00 AND y00 -> z00").unwrap().run(),
+            Err(Error::UndrivenWire("x00".to_string()))
+        );
+        let dangling = Circuit::parse("x00 AND w00 -> z00").unwrap();
+        assert_eq!(
+            dangling.simulate(1, 0),
+            Err(Error::UndrivenWire("w00".to_string()))
+        );
+        assert_eq!(
+            dangling.evaluate(|wire| (dangling.name(wire) == "x00").then_some(true)),
+            Err(Error::UndrivenWire("w00".to_string()))
+        );
+    }
+
+    #[test]
+    fn export() {
+        let circuit = Circuit::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            circuit.to_verilog("example"),
+            "\
+module example(x00, x01, x02, y00, y01, y02, z00, z01, z02);
+    input x00, x01, x02, y00, y01, y02;
+    output z00, z01, z02;
+    and g0(z00, x00, y00);
+    xor g1(z01, x01, y01);
+    or g2(z02, x02, y02);
+endmodule
+"
+        );
+
+        let dot = circuit.to_dot();
+        assert!(dot.starts_with("digraph circuit {"));
+        assert!(dot.contains("\"x00\" [shape=box];"));
+        assert!(dot.contains("\"z01\" [label=\"z01\\nXOR\", shape=diamond, peripheries=2];"));
+        assert!(dot.contains("\"y02\" -> \"z02\";"));
+    }
+}
@@ -1,28 +1,31 @@
+use crate::{
+    adder::{self, RepairReport},
+    circuit::{self, Circuit, Operation},
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Missing blank line")]
-    MissingBlankLine,
-    #[error("Missing gate output delimiter")]
-    MissingGateOutputDelimiter,
-    #[error("Missing gate left hand side")]
-    MissingGateLeftHandSide,
-    #[error("Missing gate operation")]
-    MissingGateOperation,
-    #[error("Invalid gate operation: {0}")]
-    InvalidGateOperation(String),
-    #[error("Missing gate right hand side")]
-    MissingGateRightHandSide,
+    #[error("Invalid circuit: {0}")]
+    InvalidCircuit(#[from] circuit::Error),
+    #[error("Repair failed: {0}")]
+    Repair(#[from] adder::Error),
 }
 
 pub fn solve(input: &str) -> Result<String, Error> {
-    let (_start_values, gates) = input.split_once("\n\n").ok_or(Error::MissingBlankLine)?;
-    let gates = gates
-        .lines()
-        .map(parse_logic_gate)
-        .collect::<Result<Vec<_>, Error>>()?;
+    let circuit = Circuit::parse(input)?;
+    Ok(suspicious_wires(&circuit).join(","))
+}
+
+/// Pairs up the suspicious wires into swaps, applies them and verifies the resulting adder.
+pub fn repair(input: &str) -> Result<RepairReport, Error> {
+    let circuit = Circuit::parse(input)?;
+    let suspects = suspicious_wires(&circuit);
+    Ok(adder::repair(&circuit, &suspects)?)
+}
 
+/// Gate outputs that do not fit the structure of a ripple-carry adder, sorted by name.
+pub fn suspicious_wires(circuit: &Circuit) -> Vec<&str> {
     let mut half_adds = vec![];
     let mut full_adds = vec![];
     let mut half_carries = vec![];
@@ -30,30 +33,35 @@ pub fn solve(input: &str) -> Result<String, Error> {
     let mut full_carries = vec![];
     let mut z_max = "";
 
-    for gate in gates {
-        if gate.output.starts_with("z") {
-            z_max = std::cmp::max(z_max, gate.output);
+    for gate in circuit.gates() {
+        let (lhs, rhs, output) = (
+            circuit.name(gate.lhs),
+            circuit.name(gate.rhs),
+            circuit.name(gate.output),
+        );
+        if output.starts_with("z") {
+            z_max = std::cmp::max(z_max, output);
         }
         match gate.operation {
-            LogicOperation::And => {
-                if gate.lhs.starts_with("x") && gate.rhs.starts_with("y") {
-                    half_carries.push((gate.lhs, gate.rhs, gate.output));
-                } else if gate.lhs.starts_with("y") && gate.rhs.starts_with("x") {
-                    half_carries.push((gate.rhs, gate.lhs, gate.output));
+            Operation::And => {
+                if lhs.starts_with("x") && rhs.starts_with("y") {
+                    half_carries.push((lhs, rhs, output));
+                } else if lhs.starts_with("y") && rhs.starts_with("x") {
+                    half_carries.push((rhs, lhs, output));
                 } else {
-                    forward_carries.push((gate.lhs, gate.rhs, gate.output));
+                    forward_carries.push((lhs, rhs, output));
                 }
             }
-            LogicOperation::Or => {
-                full_carries.push((gate.lhs, gate.rhs, gate.output));
+            Operation::Or => {
+                full_carries.push((lhs, rhs, output));
             }
-            LogicOperation::Xor => {
-                if gate.lhs.starts_with("x") && gate.rhs.starts_with("y") {
-                    half_adds.push((gate.lhs, gate.rhs, gate.output));
-                } else if gate.lhs.starts_with("y") && gate.rhs.starts_with("x") {
-                    half_adds.push((gate.rhs, gate.lhs, gate.output));
+            Operation::Xor => {
+                if lhs.starts_with("x") && rhs.starts_with("y") {
+                    half_adds.push((lhs, rhs, output));
+                } else if lhs.starts_with("y") && rhs.starts_with("x") {
+                    half_adds.push((rhs, lhs, output));
                 } else {
-                    full_adds.push((gate.lhs, gate.rhs, gate.output));
+                    full_adds.push((lhs, rhs, output));
                 }
             }
         }
@@ -67,16 +75,12 @@ pub fn solve(input: &str) -> Result<String, Error> {
                 .iter()
                 .any(|&(lhs, rhs, _)| lhs == half_carry || rhs == half_carry)
             {
-                #[cfg(debug_assertions)]
-                println!("half carry error: {}", half_carry);
                 results.push(half_carry);
             }
         } else if !full_carries
             .iter()
             .any(|&(lhs, rhs, _)| lhs == half_carry || rhs == half_carry)
         {
-            #[cfg(debug_assertions)]
-            println!("half carry error: {}", half_carry);
             results.push(half_carry);
         }
     }
@@ -84,16 +88,12 @@ pub fn solve(input: &str) -> Result<String, Error> {
     for &(x, _y, half_add) in half_adds.iter() {
         if x == "x00" {
             if half_add != "z00" {
-                #[cfg(debug_assertions)]
-                println!("half add error: {}", half_add);
                 results.push(half_add);
             }
         } else if !full_adds
             .iter()
             .any(|&(lhs, rhs, _)| lhs == half_add || rhs == half_add)
         {
-            #[cfg(debug_assertions)]
-            println!("half add error: {}", half_add);
             results.push(half_add);
         }
     }
@@ -103,8 +103,6 @@ pub fn solve(input: &str) -> Result<String, Error> {
             .iter()
             .any(|&(lhs, rhs, _)| lhs == forward_carry || rhs == forward_carry)
         {
-            #[cfg(debug_assertions)]
-            println!("forward carry error: {}", forward_carry);
             results.push(forward_carry);
         }
     }
@@ -115,57 +113,18 @@ pub fn solve(input: &str) -> Result<String, Error> {
                 .iter()
                 .any(|&(lhs, rhs, _)| lhs == full_carry || rhs == full_carry)
         {
-            #[cfg(debug_assertions)]
-            println!("full carry error: {}", full_carry);
             results.push(full_carry);
         }
     }
 
     for &(_, _, full_add) in full_adds.iter() {
         if !full_add.starts_with("z") {
-            #[cfg(debug_assertions)]
-            println!("full add error: {}", full_add);
             results.push(full_add);
         }
     }
 
     results.sort();
-    Ok(results.join(","))
-}
-
-#[derive(Debug, PartialEq, Eq)]
-enum LogicOperation {
-    And,
-    Or,
-    Xor,
-}
-
-struct LogicGate<'a> {
-    lhs: &'a str,
-    rhs: &'a str,
-    output: &'a str,
-    operation: LogicOperation,
-}
-
-fn parse_logic_gate(line: &str) -> Result<LogicGate<'_>, Error> {
-    let (gate, output) = line
-        .split_once(" -> ")
-        .ok_or(Error::MissingGateOutputDelimiter)?;
-    let mut parts = gate.split_whitespace();
-    let lhs = parts.next().ok_or(Error::MissingGateLeftHandSide)?;
-    let operation = match parts.next().ok_or(Error::MissingGateOperation)? {
-        "AND" => LogicOperation::And,
-        "OR" => LogicOperation::Or,
-        "XOR" => LogicOperation::Xor,
-        op => return Err(Error::InvalidGateOperation(op.to_owned())),
-    };
-    let rhs = parts.next().ok_or(Error::MissingGateRightHandSide)?;
-    Ok(LogicGate {
-        lhs,
-        rhs,
-        output,
-        operation,
-    })
+    results
 }
 
 #[cfg(test)]
@@ -180,6 +139,21 @@ mod tests {
         assert_eq!(result, "z00,z01");
     }
 
+    #[test]
+    fn repair_adder() {
+        let mut circuit = Circuit::parse(&adder::ripple_carry_adder(12)).unwrap();
+        circuit.swap_outputs("z04", "c04").unwrap();
+        circuit.swap_outputs("a07", "s07").unwrap();
+        circuit.swap_outputs("z09", "b09").unwrap();
+        let input = circuit.to_string();
+
+        assert_eq!(solve(&input).unwrap(), "a07,b09,c04,s07,z04,z09");
+        let report = repair(&input).unwrap();
+        assert!(report.is_correct());
+        assert_eq!(report.swaps.len(), 3);
+        assert!(!report.failing_before.is_empty());
+    }
+
     #[cfg(input_txt)]
     #[cfg(part2_txt)]
     #[test]
@@ -0,0 +1,518 @@
+use crate::circuit::{self, Circuit, Simulator};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+/// The number of random inputs tried on top of the structured ones.
+pub const RANDOM_CASES: usize = 1000;
+
+/// Random inputs used while searching, before a full check of each candidate.
+const QUICK_RANDOM_CASES: usize = 64;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("Invalid circuit: {0}")]
+    InvalidCircuit(#[from] circuit::Error),
+    #[error("Unknown function: {0}")]
+    UnknownFunction(String),
+    #[error("No repair with up to {0} swaps")]
+    GaveUp(usize),
+    #[error("Search gave up after trying {0} sets of swaps")]
+    CandidateLimit(usize),
+}
+
+/// The function a circuit is meant to compute from the numbers on its `x` and `y` wires.
+///
+/// The result is truncated to the width of the `z` wires.
+pub enum Spec {
+    Add,
+    And,
+    Multiply,
+    Custom(Box<dyn Fn(u128, u128) -> u128 + Send + Sync>),
+}
+
+impl Spec {
+    pub fn custom(function: impl Fn(u128, u128) -> u128 + Send + Sync + 'static) -> Self {
+        Self::Custom(Box::new(function))
+    }
+
+    pub fn expected(&self, x: u128, y: u128) -> u128 {
+        match self {
+            Spec::Add => x.wrapping_add(y),
+            Spec::And => x & y,
+            Spec::Multiply => x.wrapping_mul(y),
+            Spec::Custom(function) => function(x, y),
+        }
+    }
+}
+
+impl fmt::Debug for Spec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Spec::Add => write!(f, "Add"),
+            Spec::And => write!(f, "And"),
+            Spec::Multiply => write!(f, "Multiply"),
+            Spec::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+impl FromStr for Spec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "add" => Ok(Spec::Add),
+            "and" => Ok(Spec::And),
+            "mul" | "multiply" => Ok(Spec::Multiply),
+            _ => Err(Error::UnknownFunction(s.to_owned())),
+        }
+    }
+}
+
+/// Output bits where the circuit does not compute the function.
+///
+/// The inputs tried are every pair of single bits, every bit with and without an
+/// incoming carry, all ones, and `random_cases` random pairs.
+pub fn failing_bits(
+    circuit: &Circuit,
+    spec: &Spec,
+    random_cases: usize,
+) -> Result<Vec<usize>, circuit::Error> {
+    let mut simulator = circuit.simulator()?;
+    let cases = Cases::new(&simulator, spec, true, random_cases);
+    Ok(bits(cases.failing(&mut simulator)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The most swaps in a solution.
+    pub swaps: usize,
+    /// The most sets of swaps tried in total.
+    pub candidates: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            swaps: 4,
+            candidates: 1_000_000,
+        }
+    }
+}
+
+/// Finds the fewest swaps of gate outputs that make the circuit compute the function.
+///
+/// Only wires that a failing output bit depends on are swapped. Every set of one
+/// swap is tried, then every set of two, and so on up to `limits.swaps`, so the
+/// first repair found is as small as any. A set is only simulated if, for every
+/// failing bit, it swaps a wire that bit depends on, as the bit cannot change
+/// otherwise. [`Error::GaveUp`] means there is no repair within the limits.
+pub fn find_swaps(
+    circuit: &Circuit,
+    spec: &Spec,
+    limits: Limits,
+) -> Result<Vec<(String, String)>, Error> {
+    let simulator = circuit.simulator()?;
+    let mut search = Search {
+        quick: Cases::new(&simulator, spec, false, QUICK_RANDOM_CASES),
+        full: Cases::new(&simulator, spec, true, RANDOM_CASES),
+        wires: vec![],
+        cones: vec![],
+        broken: 0,
+        used: vec![],
+        limits,
+        candidates: 0,
+    };
+    let mut circuit = circuit.clone();
+    search.broken = search.failing(&circuit).unwrap_or_default();
+    if search.broken == 0 {
+        return Ok(vec![]);
+    }
+
+    // Each wire a failing bit depends on, with the failing bits that depend on it.
+    let z = circuit.bus('z');
+    let mut cones = vec![0u128; circuit.wires().len()];
+    for bit in bits(search.broken) {
+        for wire in z[bit].map(|wire| circuit.fan_in(wire)).unwrap_or_default() {
+            cones[wire] |= 1 << bit;
+        }
+    }
+    (search.wires, search.cones) = cones
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, cone)| cone != 0)
+        .unzip();
+    search.used = vec![false; search.wires.len()];
+
+    for depth in 1..=limits.swaps {
+        if let Some(swaps) = search.search(&mut circuit, depth, 0, 0, &mut vec![])? {
+            return Ok(swaps
+                .into_iter()
+                .map(|(a, b)| (circuit.name(a).to_owned(), circuit.name(b).to_owned()))
+                .collect());
+        }
+    }
+    Err(Error::GaveUp(limits.swaps))
+}
+
+/// Test inputs with the expected outputs.
+struct Cases {
+    inputs: Vec<(u128, u128)>,
+    expected: Vec<u128>,
+    mask: u128,
+}
+
+impl Cases {
+    /// Every pair of single bits is only included when `pairs` is set, as there are
+    /// many of them.
+    fn new(simulator: &Simulator, spec: &Spec, pairs: bool, random_cases: usize) -> Self {
+        let (x_width, y_width, z_width) = simulator.widths();
+        let (x_mask, y_mask, mask) = (mask(x_width), mask(y_width), mask(z_width));
+
+        let mut inputs = vec![(0, 0), (x_mask, y_mask), (x_mask, 1), (1, y_mask)];
+        if pairs {
+            for i in 0..x_width {
+                for j in 0..y_width {
+                    inputs.push((1 << i, 1 << j));
+                }
+            }
+        }
+        for bit in 0..x_width.max(y_width) {
+            let carry = if bit == 0 { 0 } else { 1 << (bit - 1) };
+            for (a, b, c) in (0..8).map(|n| (n & 1, n >> 1 & 1, n >> 2)) {
+                inputs.push(((a << bit) | (c * carry), (b << bit) | (c * carry)));
+            }
+        }
+        let mut random = Random(0x2024_1224);
+        inputs.extend((0..random_cases).map(|_| (random.next(), random.next())));
+
+        let inputs = inputs
+            .into_iter()
+            .map(|(x, y)| (x & x_mask, y & y_mask))
+            .collect::<Vec<_>>();
+        let expected = inputs
+            .iter()
+            .map(|&(x, y)| spec.expected(x, y) & mask)
+            .collect();
+        Self {
+            inputs,
+            expected,
+            mask,
+        }
+    }
+
+    /// The output bits that are wrong for at least one input.
+    fn failing(&self, simulator: &mut Simulator) -> u128 {
+        simulator
+            .run_batch(&self.inputs)
+            .into_iter()
+            .zip(&self.expected)
+            .fold(0, |acc, (actual, expected)| acc | actual ^ expected)
+            & self.mask
+    }
+}
+
+struct Search {
+    quick: Cases,
+    full: Cases,
+    /// The wires that may be swapped.
+    wires: Vec<usize>,
+    /// The failing bits that depend on each of `wires`.
+    cones: Vec<u128>,
+    /// The bits that fail before any swaps.
+    broken: u128,
+    /// Which of `wires` are in the current set of swaps.
+    used: Vec<bool>,
+    limits: Limits,
+    candidates: usize,
+}
+
+impl Search {
+    /// The failing output bits, or `None` if the circuit has a cycle.
+    fn failing(&self, circuit: &Circuit) -> Option<u128> {
+        let mut simulator = circuit.simulator().ok()?;
+        match self.quick.failing(&mut simulator) {
+            0 => Some(self.full.failing(&mut simulator)),
+            failing => Some(failing),
+        }
+    }
+
+    /// Tries every set of `depth` more swaps whose first wires come after index
+    /// `first` of `wires`, on top of `swaps`, which touch the cones of `reached`.
+    fn search(
+        &mut self,
+        circuit: &mut Circuit,
+        depth: usize,
+        first: usize,
+        reached: u128,
+        swaps: &mut Vec<(usize, usize)>,
+    ) -> Result<Option<Vec<(usize, usize)>>, Error> {
+        if depth == 0 {
+            self.candidates += 1;
+            if self.candidates > self.limits.candidates {
+                return Err(Error::CandidateLimit(self.limits.candidates));
+            }
+            let repaired = reached == self.broken && self.failing(circuit) == Some(0);
+            return Ok(repaired.then(|| swaps.clone()));
+        }
+        for i in first..self.wires.len() {
+            if self.used[i] {
+                continue;
+            }
+            for j in i + 1..self.wires.len() {
+                if self.used[j] {
+                    continue;
+                }
+                let (a, b) = (self.wires[i], self.wires[j]);
+                circuit.swap_output_wires(a, b)?;
+                (self.used[i], self.used[j]) = (true, true);
+                swaps.push((a, b));
+                let reached = reached | self.cones[i] | self.cones[j];
+                let solution = self.search(circuit, depth - 1, i + 1, reached, swaps);
+                swaps.pop();
+                (self.used[i], self.used[j]) = (false, false);
+                circuit.swap_output_wires(a, b)?;
+                if let Some(solution) = solution? {
+                    return Ok(Some(solution));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn mask(width: usize) -> u128 {
+    1u128
+        .checked_shl(width as u32)
+        .map_or(u128::MAX, |bit| bit - 1)
+}
+
+fn bits(mask: u128) -> Vec<usize> {
+    (0..128).filter(|bit| mask >> bit & 1 == 1).collect()
+}
+
+/// A xorshift generator, so that checks are repeatable.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u128 {
+        let mut next = || {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 as u128
+        };
+        (next() << 64) | next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adder::ripple_carry_adder;
+
+    /// Builds a netlist in the puzzle format with generated internal wire names.
+    #[derive(Default)]
+    struct Netlist {
+        lines: Vec<String>,
+    }
+
+    impl Netlist {
+        fn gate(&mut self, lhs: &str, operation: &str, rhs: &str) -> String {
+            let output = format!("w{}", self.lines.len());
+            self.lines
+                .push(format!("{lhs} {operation} {rhs} -> {output}"));
+            output
+        }
+
+        fn full_adder(&mut self, a: &str, b: &str, carry: Option<&str>) -> (String, String) {
+            let sum = self.gate(a, "XOR", b);
+            let overflow = self.gate(a, "AND", b);
+            match carry {
+                None => (sum, overflow),
+                Some(carry) => {
+                    let total = self.gate(&sum, "XOR", carry);
+                    let propagate = self.gate(&sum, "AND", carry);
+                    let carry = self.gate(&overflow, "OR", &propagate);
+                    (total, carry)
+                }
+            }
+        }
+
+        /// Drives an output wire from an internal one.
+        fn output(&mut self, name: &str, wire: &str) {
+            self.lines.push(format!("{wire} AND {wire} -> {name}"));
+        }
+    }
+
+    /// An array multiplier of two `bits` bit numbers.
+    fn multiplier(bits: usize) -> String {
+        let mut netlist = Netlist::default();
+        let mut row = (0..bits)
+            .map(|i| netlist.gate(&format!("x{i:02}"), "AND", "y00"))
+            .collect::<Vec<_>>();
+        let mut outputs = vec![];
+        for j in 1..bits {
+            outputs.push(row.remove(0));
+            let mut carry: Option<String> = None;
+            let mut next = vec![];
+            for i in 0..bits {
+                let product = netlist.gate(&format!("x{i:02}"), "AND", &format!("y{j:02}"));
+                let (sum, overflow) = match (row.get(i).cloned(), carry.take()) {
+                    (Some(previous), carry) => {
+                        netlist.full_adder(&previous, &product, carry.as_deref())
+                    }
+                    (None, Some(carry)) => netlist.full_adder(&carry, &product, None),
+                    (None, None) => (product, String::new()),
+                };
+                next.push(sum);
+                carry = Some(overflow).filter(|carry| !carry.is_empty());
+            }
+            next.extend(carry);
+            row = next;
+        }
+        outputs.extend(row);
+        for (bit, wire) in outputs.iter().enumerate() {
+            netlist.output(&format!("z{bit:02}"), wire);
+        }
+        netlist.lines.join("\n")
+    }
+
+    #[test]
+    fn multiplier_is_correct() {
+        let circuit = Circuit::parse(&multiplier(4)).unwrap();
+        for x in 0..16 {
+            for y in 0..16 {
+                assert_eq!(circuit.simulate(x, y), Ok(x * y));
+            }
+        }
+        assert_eq!(failing_bits(&circuit, &Spec::Multiply, 100), Ok(vec![]));
+        assert!(!failing_bits(&circuit, &Spec::Add, 100).unwrap().is_empty());
+    }
+
+    #[test]
+    fn repair_adder() {
+        let mut circuit = Circuit::parse(&ripple_carry_adder(6)).unwrap();
+        circuit.swap_outputs("z02", "b02").unwrap();
+        circuit.swap_outputs("s04", "a04").unwrap();
+        let mut swaps = find_swaps(&circuit, &Spec::Add, Limits::default()).unwrap();
+        swaps.sort();
+        assert_eq!(
+            swaps,
+            [
+                ("s04".to_string(), "a04".to_string()),
+                ("z02".to_string(), "b02".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn repair_two_swaps_on_one_bit() {
+        // Neither swap on its own fixes z03, so both have to be found together.
+        let mut circuit = Circuit::parse(&ripple_carry_adder(6)).unwrap();
+        circuit.swap_outputs("z03", "b03").unwrap();
+        circuit.swap_outputs("s03", "a03").unwrap();
+        assert_eq!(failing_bits(&circuit, &Spec::Add, 100).unwrap()[0], 3);
+        let limits = Limits {
+            swaps: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            find_swaps(&circuit, &Spec::Add, limits),
+            Err(Error::GaveUp(1))
+        );
+
+        let swaps = find_swaps(&circuit, &Spec::Add, Limits::default()).unwrap();
+        assert_eq!(swaps.len(), 2);
+        for (a, b) in &swaps {
+            circuit.swap_outputs(a, b).unwrap();
+        }
+        assert_eq!(failing_bits(&circuit, &Spec::Add, 100), Ok(vec![]));
+    }
+
+    #[test]
+    fn repair_and() {
+        let input = (0..6)
+            .map(|bit| format!("x{bit:02} AND y{bit:02} -> z{bit:02}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut circuit = Circuit::parse(&input).unwrap();
+        assert_eq!(
+            find_swaps(&circuit, &Spec::And, Limits::default()),
+            Ok(vec![])
+        );
+        circuit.swap_outputs("z01", "z04").unwrap();
+        assert_eq!(failing_bits(&circuit, &Spec::And, 10), Ok(vec![1, 4]));
+        assert_eq!(
+            find_swaps(&circuit, &Spec::And, Limits::default()),
+            Ok(vec![("z01".to_string(), "z04".to_string())])
+        );
+    }
+
+    #[test]
+    fn repair_multiplier() {
+        let mut circuit = Circuit::parse(&multiplier(4)).unwrap();
+        let wire = |bit: usize| circuit.driver(circuit.wire(&format!("z{bit:02}")).unwrap());
+        let (a, b) = (wire(2).unwrap().lhs, wire(5).unwrap().lhs);
+        let (a, b) = (circuit.name(a).to_owned(), circuit.name(b).to_owned());
+        circuit.swap_outputs(&a, &b).unwrap();
+        assert!(!failing_bits(&circuit, &Spec::Multiply, 100)
+            .unwrap()
+            .is_empty());
+
+        let swaps = find_swaps(&circuit, &Spec::Multiply, Limits::default()).unwrap();
+        assert_eq!(swaps.len(), 1);
+        let (c, d) = &swaps[0];
+        circuit.swap_outputs(c, d).unwrap();
+        assert_eq!(failing_bits(&circuit, &Spec::Multiply, 100), Ok(vec![]));
+    }
+
+    #[test]
+    fn custom_function() {
+        let input = (0..4)
+            .map(|bit| format!("x{bit:02} XOR y{bit:02} -> z{bit:02}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let circuit = Circuit::parse(&input).unwrap();
+        let spec = Spec::custom(|x, y| x ^ y);
+        assert_eq!(failing_bits(&circuit, &spec, 100), Ok(vec![]));
+        let spec = Spec::custom(|x, y| x | y);
+        assert_eq!(failing_bits(&circuit, &spec, 100), Ok(vec![0, 1, 2, 3]));
+        assert_eq!(
+            find_swaps(&circuit, &spec, Limits::default()),
+            Err(Error::GaveUp(4))
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let mut circuit = Circuit::parse(&ripple_carry_adder(10)).unwrap();
+        circuit.swap_outputs("z03", "b03").unwrap();
+        circuit.swap_outputs("s06", "a06").unwrap();
+        let limits = Limits {
+            swaps: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            find_swaps(&circuit, &Spec::Add, limits),
+            Err(Error::GaveUp(1))
+        );
+        let limits = Limits {
+            candidates: 10,
+            ..Default::default()
+        };
+        assert_eq!(
+            find_swaps(&circuit, &Spec::Add, limits),
+            Err(Error::CandidateLimit(10))
+        );
+    }
+
+    #[test]
+    fn parse_spec() {
+        assert!(matches!("mul".parse(), Ok(Spec::Multiply)));
+        assert_eq!(
+            "sub".parse::<Spec>().unwrap_err(),
+            Error::UnknownFunction("sub".to_string())
+        );
+    }
+}
@@ -1,6 +1,8 @@
+use aoc_common::input;
 use day25::{part1, INPUT};
 
-fn main() -> Result<(), part1::Error> {
-    println!("{:?}", part1::solve(INPUT)?);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let input = input::load(25, INPUT)?;
+    println!("{:?}", part1::solve(&input)?);
     Ok(())
 }